@@ -22,12 +22,18 @@ int main()
     u32 n = 0;
     u32 r = 0;
     u32 p = 0;
+    kdf_kind kind = KDF_SCRYPT;
+    u32 c = 0;
+    char prf[PRF_LEN] = {0};
     kdfparams kdf_params = {
         .dklen = &dklen,
         .n = &n,
         .r = &r,
         .p = &p,
         .salt = { salt, SALT_LEN },
+        .kdf = &kind,
+        .c = &c,
+        .prf = { prf, PRF_LEN },
     };
 
     char mac[MAC_LEN] = {0};
@@ -56,9 +62,14 @@ int main()
     printf("ciphertext: %s\n", k.ciphertext.str);
     printf("kdf: %s\n", k.kdf.str);
     printf("dklen: %zu\n", *k.kdfparams.dklen);
-    printf("n: %d\n", *k.kdfparams.n);
-    printf("r: %d\n", *k.kdfparams.r);
-    printf("p: %d\n", *k.kdfparams.p);
+    if (*k.kdfparams.kdf == KDF_SCRYPT) {
+        printf("n: %d\n", *k.kdfparams.n);
+        printf("r: %d\n", *k.kdfparams.r);
+        printf("p: %d\n", *k.kdfparams.p);
+    } else {
+        printf("c: %d\n", *k.kdfparams.c);
+        printf("prf: %s\n", k.kdfparams.prf.str);
+    }
     printf("salt: %s\n", k.kdfparams.salt.str);
     printf("mac: %s\n", k.mac.str);
     printf("address: %s\n", address.str);
@@ -1,17 +1,42 @@
+#include <stdbool.h>
+
+#define u8 u_int8_t
 #define u32 u_int32_t
+#define u64 u_int64_t
 #define usize size_t
 
 #define CIPHER_LEN 11 + 1
 #define IV_LEN 32 + 1
 #define CIPHERTEXT_LEN 64 + 1
+#define MNEMONIC_CIPHERTEXT_LEN 430 + 1
 #define KDF_LEN 6 + 1
 #define SALT_LEN 32 + 1
+// Other wallets (geth, MetaMask) write 32-byte salts
+#define IMPORTED_SALT_LEN 64 + 1
+#define PRF_LEN 11 + 1
 #define MAC_LEN 64 + 1
+#define SECRET_KEY_LEN 32
+#define WIF_LEN 52 + 1
+#define MNEMONIC_LEN 215 + 1
+#define SEED_LEN 64
+#define DERIVATION_HARDENED 0x80000000
+#define DERIVATION_PATH_LEN 5
+#define SOLANA_KEYPAIR_JSON_LEN 257 + 1
+#define SOLANA_KEYPAIR_BASE58_LEN 88 + 1
+#define SUI_PRIVATE_KEY_BECH32_LEN 70 + 1
+#define SUI_PRIVATE_KEY_BASE64_LEN 44 + 1
 
 #define APTOS_ADDRESS_LEN 64 + 1
 #define BITCOIN_ADDRESS_LEN 34 + 1
+#define BITCOIN_P2WPKH_ADDRESS_LEN 42 + 1
+#define BITCOIN_P2TR_ADDRESS_LEN 62 + 1
+#define BITCOIN_REGTEST_P2WPKH_ADDRESS_LEN 44 + 1
+#define BITCOIN_REGTEST_P2TR_ADDRESS_LEN 64 + 1
 #define ETHEREUM_ADDRESS_LEN 40 + 1
+#define ETHEREUM_CHECKSUM_ADDRESS_LEN 42 + 1
 #define LITECOIN_ADDRESS_LEN BITCOIN_ADDRESS_LEN
+#define LITECOIN_P2WPKH_ADDRESS_LEN 43 + 1
+#define LITECOIN_TESTNET_P2WPKH_ADDRESS_LEN 44 + 1
 #define SOLANA_ADDRESS_LEN 44 + 1
 #define SUI_ADDRESS_LEN 64 + 1
 #define TRON_ADDRESS_LEN 34 + 1
@@ -22,11 +47,29 @@ typedef struct sized_str
     usize len;
 } sized_str;
 
+typedef struct sized_bytes
+{
+    u8 *bytes;
+    usize len;
+} sized_bytes;
+
+typedef struct sized_bytes_const
+{
+    const u8 *bytes;
+    usize len;
+} sized_bytes_const;
+
 typedef struct cipherparams
 {
     sized_str iv;
 } cipherparams;
 
+typedef enum kdf_kind
+{
+    KDF_SCRYPT,
+    KDF_PBKDF2,
+} kdf_kind;
+
 typedef struct kdfparams
 {
     usize *dklen;
@@ -34,8 +77,37 @@ typedef struct kdfparams
     u32 *r;
     u32 *p;
     sized_str salt;
+    kdf_kind *kdf;
+    u32 *c;
+    sized_str prf;
 } kdfparams;
 
+typedef enum scrypt_preset
+{
+    SCRYPT_PRESET_DEFAULT,
+    SCRYPT_PRESET_LIGHT,
+    SCRYPT_PRESET_STANDARD,
+    SCRYPT_PRESET_CUSTOM,
+} scrypt_preset;
+
+typedef struct kdf_options
+{
+    // A kdf_kind value
+    u32 kdf;
+    // A scrypt_preset value
+    u32 scrypt_preset;
+    u8 log_n;
+    u32 r;
+    u32 p;
+} kdf_options;
+
+typedef enum plaintext_kind
+{
+    PLAINTEXT_KIND_SECRET_KEY,
+    PLAINTEXT_KIND_ENTROPY,
+    PLAINTEXT_KIND_MNEMONIC,
+} plaintext_kind;
+
 typedef struct keystore
 {
     sized_str cipher;
@@ -44,6 +116,8 @@ typedef struct keystore
     sized_str kdf;
     kdfparams kdfparams;
     sized_str mac;
+    // A plaintext_kind value, may be NULL for keystores of secret keys only
+    u32 *plaintext_kind;
 } keystore;
 
 typedef enum network
@@ -57,5 +131,83 @@ typedef enum network
     NETWORK_TRON,
 } network;
 
+typedef enum address_kind
+{
+    ADDRESS_KIND_DEFAULT,
+    ADDRESS_KIND_P2PKH,
+    ADDRESS_KIND_P2PKH_UNCOMPRESSED,
+    ADDRESS_KIND_P2SH_P2WPKH,
+    ADDRESS_KIND_P2WPKH,
+    ADDRESS_KIND_P2TR,
+    ADDRESS_KIND_EIP55,
+    ADDRESS_KIND_EIP1191,
+    ADDRESS_KIND_SECP256K1,
+    ADDRESS_KIND_SECP256R1,
+} address_kind;
+
+typedef enum chain
+{
+    CHAIN_MAINNET,
+    CHAIN_TESTNET,
+    CHAIN_SIGNET,
+    CHAIN_REGTEST,
+} chain;
+
+typedef struct address_options
+{
+    // An address_kind value
+    u32 kind;
+    // A chain value
+    u32 chain;
+    u64 chain_id;
+} address_options;
+
+typedef struct derivation_path
+{
+    const u32 *indexes;
+    usize len;
+} derivation_path;
+
+typedef struct derivation_path_mut
+{
+    u32 *indexes;
+    usize len;
+} derivation_path_mut;
+
+typedef enum solana_keypair_format
+{
+    SOLANA_KEYPAIR_FORMAT_JSON,
+    SOLANA_KEYPAIR_FORMAT_BASE58,
+} solana_keypair_format;
+
+typedef enum sui_private_key_format
+{
+    SUI_PRIVATE_KEY_FORMAT_BECH32,
+    SUI_PRIVATE_KEY_FORMAT_BASE64,
+} sui_private_key_format;
+
 extern char *init_keystore(keystore *k, sized_str address, sized_str password, network network);
+extern char *init_keystore_with_options(keystore *k, sized_str address, sized_str password, network network, const address_options *address_options, const kdf_options *kdf_options);
+extern char *init_keystore_from_secret_key(keystore *k, sized_str address, sized_str password, network network, const address_options *address_options, const kdf_options *kdf_options, sized_bytes_const secret_key);
+extern char *init_keystore_with_wif(keystore *k, sized_str address, sized_str password, network network, const address_options *address_options, const kdf_options *kdf_options, sized_str wif);
+extern char *init_keystore_from_wif(keystore *k, sized_str address, sized_str password, network network, const address_options *address_options, const kdf_options *kdf_options, sized_str wif);
+extern char *init_keystore_from_mnemonic(keystore *k, sized_str address, sized_str password, network network, const address_options *address_options, const kdf_options *kdf_options, sized_str mnemonic, sized_str passphrase, derivation_path path);
+extern char *init_mnemonic_keystore(keystore *k, sized_str password, const kdf_options *kdf_options, u32 plaintext_kind, sized_str mnemonic);
+extern char *default_derivation_path(network network, u32 index, derivation_path_mut path, usize *path_len);
+extern char *parse_derivation_path(network network, const address_options *address_options, sized_str input, derivation_path_mut path, usize *path_len);
+extern char *derivation_purpose_and_coin_type(network network, const address_options *address_options, u32 *purpose, u32 *coin_type);
+extern char *init_keystore_from_solana_keypair(keystore *k, sized_str address, sized_str password, const kdf_options *kdf_options, u32 format, sized_str keypair);
+extern char *init_keystore_from_sui_private_key(keystore *k, sized_str address, sized_str password, const kdf_options *kdf_options, u32 format, sized_str private_key);
+extern char *decrypt_keystore(const keystore *k, sized_str password, sized_bytes secret_key);
+extern char *decrypt_keystore_to_wif(const keystore *k, sized_str password, network network, const address_options *address_options, sized_str wif);
+extern char *decrypt_keystore_to_solana_keypair(const keystore *k, sized_str password, u32 format, sized_str keypair);
+extern char *decrypt_keystore_to_sui_private_key(const keystore *k, sized_str password, sized_str address, u32 format, sized_str private_key);
+extern char *decrypt_keystore_to_mnemonic(const keystore *k, sized_str password, sized_str mnemonic);
+extern char *verify_keystore_password(const keystore *k, sized_str password, bool *is_valid);
+extern char *reencrypt_keystore(keystore *k, sized_str old_password, sized_str new_password, const kdf_options *kdf_options);
+extern char *keystore_to_json(const keystore *k, sized_str address, network network, sized_str json, usize *json_len);
+extern char *keystore_from_json(keystore *k, sized_str json);
+extern char *generate_mnemonic(usize word_count, sized_str mnemonic);
+extern char *validate_mnemonic(sized_str mnemonic, bool *is_valid);
+extern char *mnemonic_to_seed(sized_str mnemonic, sized_str passphrase, sized_bytes seed);
 extern void free_cstring(char *ptr);
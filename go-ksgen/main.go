@@ -6,26 +6,48 @@ package main
 */
 import "C"
 import (
+	"bytes"
 	"fmt"
 	"runtime"
 	"unsafe"
 )
 
 const (
-	CIPHER_LEN     = C.CIPHER_LEN
-	IV_LEN         = C.IV_LEN
-	CIPHERTEXT_LEN = C.CIPHERTEXT_LEN
-	KDF_LEN        = C.KDF_LEN
-	SALT_LEN       = C.SALT_LEN
-	MAC_LEN        = C.MAC_LEN
-
-	APTOS_ADDRESS_LEN    = C.APTOS_ADDRESS_LEN
-	BITCOIN_ADDRESS_LEN  = C.BITCOIN_ADDRESS_LEN
-	ETHEREUM_ADDRESS_LEN = C.ETHEREUM_ADDRESS_LEN
-	LITECOIN_ADDRESS_LEN = C.LITECOIN_ADDRESS_LEN
-	SOLANA_ADDRESS_LEN   = C.SOLANA_ADDRESS_LEN
-	SUI_ADDRESS_LEN      = C.SUI_ADDRESS_LEN
-	TRON_ADDRESS_LEN     = C.TRON_ADDRESS_LEN
+	CIPHER_LEN              = C.CIPHER_LEN
+	IV_LEN                  = C.IV_LEN
+	CIPHERTEXT_LEN          = C.CIPHERTEXT_LEN
+	MNEMONIC_CIPHERTEXT_LEN = C.MNEMONIC_CIPHERTEXT_LEN
+	KDF_LEN                 = C.KDF_LEN
+	SALT_LEN                = C.SALT_LEN
+	IMPORTED_SALT_LEN       = C.IMPORTED_SALT_LEN
+	MAC_LEN                 = C.MAC_LEN
+	PRF_LEN                 = C.PRF_LEN
+	WIF_LEN                 = C.WIF_LEN
+	MNEMONIC_LEN            = C.MNEMONIC_LEN
+	SEED_LEN                = C.SEED_LEN
+
+	DERIVATION_HARDENED = C.DERIVATION_HARDENED
+	DERIVATION_PATH_LEN = C.DERIVATION_PATH_LEN
+
+	SOLANA_KEYPAIR_JSON_LEN    = C.SOLANA_KEYPAIR_JSON_LEN
+	SOLANA_KEYPAIR_BASE58_LEN  = C.SOLANA_KEYPAIR_BASE58_LEN
+	SUI_PRIVATE_KEY_BECH32_LEN = C.SUI_PRIVATE_KEY_BECH32_LEN
+	SUI_PRIVATE_KEY_BASE64_LEN = C.SUI_PRIVATE_KEY_BASE64_LEN
+
+	APTOS_ADDRESS_LEN                   = C.APTOS_ADDRESS_LEN
+	BITCOIN_ADDRESS_LEN                 = C.BITCOIN_ADDRESS_LEN
+	BITCOIN_P2WPKH_ADDRESS_LEN          = C.BITCOIN_P2WPKH_ADDRESS_LEN
+	BITCOIN_P2TR_ADDRESS_LEN            = C.BITCOIN_P2TR_ADDRESS_LEN
+	BITCOIN_REGTEST_P2WPKH_ADDRESS_LEN  = C.BITCOIN_REGTEST_P2WPKH_ADDRESS_LEN
+	BITCOIN_REGTEST_P2TR_ADDRESS_LEN    = C.BITCOIN_REGTEST_P2TR_ADDRESS_LEN
+	ETHEREUM_ADDRESS_LEN                = C.ETHEREUM_ADDRESS_LEN
+	ETHEREUM_CHECKSUM_ADDRESS_LEN       = C.ETHEREUM_CHECKSUM_ADDRESS_LEN
+	LITECOIN_ADDRESS_LEN                = C.LITECOIN_ADDRESS_LEN
+	LITECOIN_P2WPKH_ADDRESS_LEN         = C.LITECOIN_P2WPKH_ADDRESS_LEN
+	LITECOIN_TESTNET_P2WPKH_ADDRESS_LEN = C.LITECOIN_TESTNET_P2WPKH_ADDRESS_LEN
+	SOLANA_ADDRESS_LEN                  = C.SOLANA_ADDRESS_LEN
+	SUI_ADDRESS_LEN                     = C.SUI_ADDRESS_LEN
+	TRON_ADDRESS_LEN                    = C.TRON_ADDRESS_LEN
 
 	NETWORK_APTOS    = C.NETWORK_APTOS
 	NETWROK_BITCOIN  = C.NETWROK_BITCOIN
@@ -34,6 +56,35 @@ const (
 	NETWORK_SOLANA   = C.NETWORK_SOLANA
 	NETWORK_SUI      = C.NETWORK_SUI
 	NETWORK_TRON     = C.NETWORK_TRON
+
+	KDF_SCRYPT = C.KDF_SCRYPT
+	KDF_PBKDF2 = C.KDF_PBKDF2
+
+	PLAINTEXT_KIND_SECRET_KEY = C.PLAINTEXT_KIND_SECRET_KEY
+	PLAINTEXT_KIND_ENTROPY    = C.PLAINTEXT_KIND_ENTROPY
+	PLAINTEXT_KIND_MNEMONIC   = C.PLAINTEXT_KIND_MNEMONIC
+
+	ADDRESS_KIND_DEFAULT            = C.ADDRESS_KIND_DEFAULT
+	ADDRESS_KIND_P2PKH              = C.ADDRESS_KIND_P2PKH
+	ADDRESS_KIND_P2PKH_UNCOMPRESSED = C.ADDRESS_KIND_P2PKH_UNCOMPRESSED
+	ADDRESS_KIND_P2SH_P2WPKH        = C.ADDRESS_KIND_P2SH_P2WPKH
+	ADDRESS_KIND_P2WPKH             = C.ADDRESS_KIND_P2WPKH
+	ADDRESS_KIND_P2TR               = C.ADDRESS_KIND_P2TR
+	ADDRESS_KIND_EIP55              = C.ADDRESS_KIND_EIP55
+	ADDRESS_KIND_EIP1191            = C.ADDRESS_KIND_EIP1191
+	ADDRESS_KIND_SECP256K1          = C.ADDRESS_KIND_SECP256K1
+	ADDRESS_KIND_SECP256R1          = C.ADDRESS_KIND_SECP256R1
+
+	CHAIN_MAINNET = C.CHAIN_MAINNET
+	CHAIN_TESTNET = C.CHAIN_TESTNET
+	CHAIN_SIGNET  = C.CHAIN_SIGNET
+	CHAIN_REGTEST = C.CHAIN_REGTEST
+
+	SOLANA_KEYPAIR_FORMAT_JSON   = C.SOLANA_KEYPAIR_FORMAT_JSON
+	SOLANA_KEYPAIR_FORMAT_BASE58 = C.SOLANA_KEYPAIR_FORMAT_BASE58
+
+	SUI_PRIVATE_KEY_FORMAT_BECH32 = C.SUI_PRIVATE_KEY_FORMAT_BECH32
+	SUI_PRIVATE_KEY_FORMAT_BASE64 = C.SUI_PRIVATE_KEY_FORMAT_BASE64
 )
 
 // Your password is securely stored in encrypted config or HSM storage
@@ -61,6 +112,9 @@ type kdfParams struct {
 	r     uint32
 	p     uint32
 	salt  string
+	kdf   uint32
+	c     uint32
+	prf   string
 }
 
 func main() {
@@ -109,18 +163,36 @@ func main() {
 	saltPtr := &salt[0]
 	pinner.Pin(saltPtr)
 
+	var (
+		kdfKind C.kdf_kind = KDF_SCRYPT
+		c       C.u32 = 0
+	)
+	kdfKindPtr := &kdfKind
+	pinner.Pin(kdfKindPtr)
+
+	cPtr := &c
+	pinner.Pin(cPtr)
+
+	prf := [PRF_LEN]byte{}
+	prfPtr := &prf[0]
+	pinner.Pin(prfPtr)
+
 	kdfparams := C.kdfparams{
 		dklen: dklenPtr,
 		n:     nPtr,
 		r:     rPtr,
 		p:     pPtr,
 		salt:  newSizedStr(saltPtr, SALT_LEN),
+		kdf:   kdfKindPtr,
+		c:     cPtr,
+		prf:   newSizedStr(prfPtr, PRF_LEN),
 	}
 
 	mac := [MAC_LEN]byte{}
 	macPtr := &mac[0]
 	pinner.Pin(macPtr)
 
+	// plaintext_kind stays nil, the keystore holds a secret key
 	k := C.keystore{
 		cipher:       newSizedStr(cipherPtr, CIPHER_LEN),
 		cipherparams: cipherparams,
@@ -145,6 +217,43 @@ func main() {
 
 	fmt.Printf("%+v\n", newKeystore(k))
 	fmt.Println("address: ", C.GoString(addressStr.str))
+
+	// Without the NUL terminator
+	addressInput := newSizedStr(addressPtr, uint(bytes.IndexByte(address[:], 0)))
+	json, jsonErr := keystoreToJSON(&k, addressInput, NETWORK_ETHEREUM)
+	if jsonErr != nil {
+		fmt.Println(jsonErr)
+		return
+	}
+
+	fmt.Println("json: ", json)
+}
+
+func keystoreToJSON(k *C.keystore, address C.sized_str, network C.network) (string, error) {
+	var jsonLen C.usize = 0
+
+	// Queries the buffer len first
+	err := C.keystore_to_json(k, address, network, C.sized_str{}, &jsonLen)
+	if err != nil {
+		defer C.free_cstring(err)
+		return "", fmt.Errorf("%s", C.GoString(err))
+	}
+
+	json := make([]byte, jsonLen)
+
+	var pinner runtime.Pinner
+	defer pinner.Unpin()
+
+	jsonPtr := &json[0]
+	pinner.Pin(jsonPtr)
+
+	err = C.keystore_to_json(k, address, network, newSizedStr(jsonPtr, uint(jsonLen)), &jsonLen)
+	if err != nil {
+		defer C.free_cstring(err)
+		return "", fmt.Errorf("%s", C.GoString(err))
+	}
+
+	return string(json[:jsonLen-1]), nil
 }
 
 func newSizedStr(str *byte, len uint) C.sized_str {
@@ -178,5 +287,8 @@ func newKdfParams(p C.kdfparams) kdfParams {
 		r:     uint32(*p.r),
 		p:     uint32(*p.p),
 		salt:  C.GoString(p.salt.str),
+		kdf:   uint32(*p.kdf),
+		c:     uint32(*p.c),
+		prf:   C.GoString(p.prf.str),
 	}
 }
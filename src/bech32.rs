@@ -0,0 +1,253 @@
+#[cfg(feature = "sui")]
+use zeroize::Zeroizing;
+
+use crate::error::{KsgenError, Result};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const BECH32_CONST: u32 = 1;
+#[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+const BECH32M_CONST: u32 = 0x2bc830a3;
+const CHECKSUM_LEN: usize = 6;
+
+// BIP173 limits the whole string to 90 characters
+const MAX_LEN: usize = 90;
+
+// The shortest HRP and the separator leave this many groups of 5 bits
+const MAX_DATA_LEN: usize = MAX_LEN - 2 - CHECKSUM_LEN;
+
+/// Encodes a SegWit address into the output, returns the written len.
+/// Version 0 programs use bech32 (BIP173), later versions use bech32m (BIP350)
+#[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+pub fn encode_segwit(hrp: &str, witness_version: u8, program: &[u8], output: &mut [u8]) -> Result<usize> {
+    let mut data = [0; MAX_DATA_LEN];
+    data[0] = witness_version;
+    let data_len = 1 + convert_bits(program, &mut data[1..])?;
+
+    let checksum_const = if witness_version == 0 { BECH32_CONST } else { BECH32M_CONST };
+    encode_internal(hrp, &data[..data_len], checksum_const, output)
+}
+
+/// Encodes arbitrary bytes with the bech32 (BIP173) checksum, returns the written len
+#[cfg(feature = "sui")]
+pub fn encode(hrp: &str, input: &[u8], output: &mut [u8]) -> Result<usize> {
+    // May hold secret keys
+    let mut data = Zeroizing::new([0; MAX_DATA_LEN]);
+    let data_len = convert_bits(input, data.as_mut_slice())?;
+
+    encode_internal(hrp, &data[..data_len], BECH32_CONST, output)
+}
+
+/// Decodes a bech32 (BIP173) string with the expected HRP into the output, returns the written len
+#[cfg(feature = "sui")]
+pub fn decode(input: &[u8], hrp: &str, output: &mut [u8]) -> Result<usize> {
+    if input.len() > MAX_LEN {
+        return Err(KsgenError::invalid_bech32(format!("the string is longer than {} characters", MAX_LEN)));
+    }
+    if input.iter().any(u8::is_ascii_lowercase) && input.iter().any(u8::is_ascii_uppercase) {
+        return Err(KsgenError::invalid_bech32("mixed case".into()));
+    }
+
+    let separator = input.iter()
+        .rposition(|x| x == &b'1')
+        .ok_or_else(|| KsgenError::invalid_bech32("no separator".into()))?;
+
+    let (input_hrp, input_data) = (&input[..separator], &input[separator + 1..]);
+    if !input_hrp.eq_ignore_ascii_case(hrp.as_bytes()) {
+        return Err(KsgenError::invalid_bech32(format!("expected the {} prefix", hrp)));
+    }
+    if input_data.len() < CHECKSUM_LEN {
+        return Err(KsgenError::invalid_bech32("the checksum is too short".into()));
+    }
+    // Only an empty HRP leaves room for more
+    if input_data.len() > MAX_DATA_LEN + CHECKSUM_LEN {
+        return Err(KsgenError::invalid_bech32(format!("the data is longer than {} characters", MAX_DATA_LEN)));
+    }
+
+    // The data groups followed by the checksum
+    let mut data = Zeroizing::new([0; MAX_DATA_LEN + CHECKSUM_LEN]);
+    for (o, x) in data.iter_mut().zip(input_data) {
+        *o = CHARSET.iter()
+            .position(|c| c == &x.to_ascii_lowercase())
+            .ok_or_else(|| KsgenError::invalid_bech32(format!("invalid character ({})", *x as char)))? as u8;
+    }
+    let data = &data[..input_data.len()];
+
+    let values = hrp_expand(hrp.as_bytes()).chain(data.iter().copied());
+    if polymod(values) != BECH32_CONST {
+        return Err(KsgenError::InvalidChecksumErr);
+    }
+
+    regroup_bits(&data[..data.len() - CHECKSUM_LEN], output)
+}
+
+fn encode_internal(hrp: &str, data: &[u8], checksum_const: u32, output: &mut [u8]) -> Result<usize> {
+    let hrp = hrp.as_bytes();
+    let len = hrp.len() + 1 + data.len() + CHECKSUM_LEN;
+    if output.len() < len {
+        return Err(KsgenError::invalid_output(len, output.len(), "bech32".into()));
+    }
+
+    let checksum = checksum(hrp, data, checksum_const);
+
+    output[..hrp.len()].copy_from_slice(hrp);
+    output[hrp.len()] = b'1';
+
+    let encoded = data.iter().chain(checksum.iter());
+    for (o, &x) in output[hrp.len() + 1..len].iter_mut().zip(encoded) {
+        *o = CHARSET[x as usize];
+    }
+
+    Ok(len)
+}
+
+// Regroups 8-bit bytes into 5-bit groups, padding the last one with zeros
+fn convert_bits(input: &[u8], output: &mut [u8]) -> Result<usize> {
+    let output_len = (input.len() * 8).div_ceil(5);
+    if output.len() < output_len {
+        return Err(KsgenError::invalid_output(output_len, output.len(), "bech32 data".into()));
+    }
+
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut len = 0;
+
+    for &x in input {
+        acc = (acc << 8) | x as u32;
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            output[len] = ((acc >> bits) & 31) as u8;
+            len += 1;
+        }
+    }
+
+    if bits > 0 {
+        output[len] = ((acc << (5 - bits)) & 31) as u8;
+        len += 1;
+    }
+
+    Ok(len)
+}
+
+// Regroups 5-bit groups back into bytes, the padding must be shorter than 5 bits and zeroed
+#[cfg(feature = "sui")]
+fn regroup_bits(input: &[u8], output: &mut [u8]) -> Result<usize> {
+    let output_len = input.len() * 5 / 8;
+    if output.len() < output_len {
+        return Err(KsgenError::invalid_output(output_len, output.len(), "bech32 data".into()));
+    }
+
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut len = 0;
+
+    for &x in input {
+        acc = ((acc << 5) | x as u32) & 0xfff;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            output[len] = (acc >> bits) as u8;
+            len += 1;
+        }
+    }
+
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err(KsgenError::invalid_bech32("invalid padding".into()));
+    }
+
+    Ok(len)
+}
+
+fn checksum(hrp: &[u8], data: &[u8], checksum_const: u32) -> [u8; CHECKSUM_LEN] {
+    let values = hrp_expand(hrp)
+        .chain(data.iter().copied())
+        .chain([0; CHECKSUM_LEN]);
+
+    let polymod = polymod(values) ^ checksum_const;
+
+    let mut checksum = [0; CHECKSUM_LEN];
+    for (i, x) in checksum.iter_mut().enumerate() {
+        *x = ((polymod >> (5 * (5 - i))) & 31) as u8;
+    }
+
+    checksum
+}
+
+fn hrp_expand(hrp: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    hrp.iter().map(|x| x >> 5)
+        .chain([0])
+        .chain(hrp.iter().map(|x| x & 31))
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    let mut chk = 1u32;
+
+    for x in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ x as u32;
+
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+
+    chk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+    fn test_encode_segwit() {
+        // BIP173 test vectors
+        let program = hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let mut output = [0; 42];
+        let len = encode_segwit("bc", 0, &program, &mut output).unwrap();
+        assert_eq!(&output[..len], b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+
+        let program = hex::decode("1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262").unwrap();
+        let mut output = [0; 62];
+        let len = encode_segwit("tb", 0, &program, &mut output).unwrap();
+        assert_eq!(&output[..len], b"tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7");
+
+        assert!(encode_segwit("bc", 0, &program, &mut [0; 42]).is_err());
+
+        // BIP350 test vector
+        let program = hex::decode("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap();
+        let mut output = [0; 62];
+        let len = encode_segwit("bc", 1, &program, &mut output).unwrap();
+        assert_eq!(&output[..len], b"bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0");
+    }
+
+    #[test]
+    #[cfg(feature = "sui")]
+    fn test_encode_decode() {
+        // BIP173 test vector
+        let input = "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw";
+        let mut output = [0; 20];
+        let len = decode(input.as_bytes(), "abcdef", &mut output).unwrap();
+        assert_eq!(hex::encode(&output[..len]), "00443214c74254b635cf84653a56d7c675be77df");
+
+        let mut encoded = [0; 45];
+        let len = encode("abcdef", &output, &mut encoded).unwrap();
+        assert_eq!(&encoded[..len], input.as_bytes());
+
+        assert_eq!(decode(b"A12UEL5L", "a", &mut []).unwrap(), 0);
+        assert!(decode(b"A12uEL5L", "a", &mut []).is_err());
+        assert!(decode(b"a12uel5m", "a", &mut []).is_err());
+        assert!(decode(input.as_bytes(), "bc", &mut output).is_err());
+
+        // The longest strings fill the whole data buffer
+        let input = [b"a1".as_slice(), &[b'q'; MAX_LEN - 2]].concat();
+        assert!(matches!(decode(&input, "a", &mut [0; 60]), Err(KsgenError::InvalidChecksumErr)));
+        let input = [b"1".as_slice(), &[b'q'; MAX_LEN - 1]].concat();
+        assert!(matches!(decode(&input, "", &mut [0; 60]), Err(KsgenError::InvalidBech32Err { .. })));
+    }
+}
\ No newline at end of file
@@ -2,22 +2,54 @@ use sha2::{Digest, Sha256};
 
 use crate::error::Result;
 
-pub fn bs58_check(input: &mut [u8], output: &mut [u8]) -> Result<()> {
-    let mut chk = [0; 32];
+#[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+use crate::error::KsgenError;
+
+/// Fills the last 4 bytes of the input with the checksum and encodes it, returns the written len
+pub fn bs58_check(input: &mut [u8], output: &mut [u8]) -> Result<usize> {
     let end = input.len() - 4;
+    let chk = checksum(&input[..end]);
+
+    input[end..].copy_from_slice(&chk);
+
+    let len = bs58::encode(input)
+        .onto(output)?;
+
+    Ok(len)
+}
+
+/// Decodes the input and verifies the 4-byte checksum, returns the payload len without the checksum
+#[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+pub fn bs58_check_decode(input: &[u8], output: &mut [u8]) -> Result<usize> {
+    let len = bs58::decode(input)
+        .onto(&mut *output)?;
+
+    if len < 4 {
+        return Err(KsgenError::InvalidChecksumErr);
+    }
+
+    let end = len - 4;
+    if checksum(&output[..end]) != output[end..len] {
+        return Err(KsgenError::InvalidChecksumErr);
+    }
+
+    Ok(end)
+}
+
+// The first 4 bytes of the double SHA256 hash
+fn checksum(input: &[u8]) -> [u8; 4] {
+    let mut chk = [0; 32];
 
     let mut hasher = Sha256::new();
-    hasher.update(&input[..end]);
+    hasher.update(input);
     hasher.finalize_into(chk.as_mut_slice().into());
 
     let mut hasher = Sha256::new();
-    hasher.update(&chk);
+    hasher.update(chk);
     hasher.finalize_into(chk.as_mut_slice().into());
 
-    input[end..].copy_from_slice(&mut chk[..4]);
-
-    bs58::encode(input)
-        .onto(output)?;
+    let mut output = [0; 4];
+    output.copy_from_slice(&chk[..4]);
 
-    Ok(())
+    output
 }
\ No newline at end of file
@@ -1,13 +1,34 @@
 use sha3::{Digest, Sha3_256};
 
-use crate::{error::Result, kp::{derived_kp::KeypairDerived, ed25519_kp::Keypair}};
+use crate::{
+    error::Result,
+    hd::{path, HARDENED},
+    keystore_ffi::{AddressKind, Chain, Network},
+    kp::{derived_kp::KeypairDerived, ed25519_kp::Keypair},
+};
 
 const ADDRESS_LEN_INTERNAL: usize = 64;
 pub const ADDRESS_LEN: usize = ADDRESS_LEN_INTERNAL + 1;
 
+pub const DERIVATION_PATH_LEN: usize = 5;
+
+/// The Petra path `m/44'/637'/i'/0'/0'` of the account index
+pub fn derivation_path(index: u32) -> [u32; DERIVATION_PATH_LEN] {
+    let purpose = path::purpose(&Network::Aptos, AddressKind::Default);
+    let coin_type = path::coin_type(&Network::Aptos, Chain::Mainnet);
+
+    [HARDENED | purpose, HARDENED | coin_type, HARDENED | index, HARDENED, HARDENED]
+}
+
 pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    let kp = Keypair::generate();
+    derive_keypair(Keypair::generate())
+}
+
+pub fn keypair_from_secret(secret_key: &[u8; 32]) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    derive_keypair(Keypair::from_secret_key(secret_key))
+}
 
+fn derive_keypair(kp: Keypair) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
     let secret_key = kp.secret_key();
     let public_key = kp.public_key();
 
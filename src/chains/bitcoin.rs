@@ -1,35 +1,359 @@
 use ripemd::Ripemd160;
 use sha2::{Sha256, Digest};
 
-use crate::{bs58_check, error::Result, kp::{derived_kp::KeypairDerived, secp256k1_kp::Keypair}};
+use zeroize::Zeroizing;
+
+use crate::{bech32, bs58_check, error::{KsgenError, Result}, kp::{derived_kp::KeypairDerived, secp256k1_kp::Keypair}};
 
 pub(super) const ADDRESS_LEN_INTERNAL: usize = 34;
 pub const ADDRESS_LEN: usize = ADDRESS_LEN_INTERNAL + 1;
 
-pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    get_keypair_internal(0x00)
+// Bech32 address len without the HRP: separator, witness version,
+// 32 (P2WPKH) or 52 (P2TR) groups of the program and 6 checksum characters
+const P2WPKH_DATA_LEN: usize = 40;
+const P2TR_DATA_LEN: usize = 60;
+
+// Fits the longest supported address kind with the 4-character "bcrt" HRP
+pub(super) const MAX_ADDRESS_LEN_INTERNAL: usize = 4 + P2TR_DATA_LEN;
+
+// Compressed keys are 51 characters long, uncompressed ones are 52
+pub const WIF_LEN_INTERNAL: usize = 52;
+
+// Prefix byte + 32 bytes of the key + 0x01 compression flag + 4 bytes for the checksum
+const WIF_PAYLOAD_LEN: usize = 38;
+
+pub struct NetworkParams {
+    pub(super) p2pkh_prefix: u8,
+    pub(super) p2sh_prefix: u8,
+    pub(super) bech32_hrp: &'static str,
+    pub(super) wif_prefix: u8,
+}
+
+#[cfg(feature = "bitcoin")]
+const MAINNET: NetworkParams = NetworkParams {
+    p2pkh_prefix: 0x00,
+    p2sh_prefix: 0x05,
+    bech32_hrp: "bc",
+    wif_prefix: 0x80,
+};
+
+#[cfg(feature = "bitcoin")]
+const TESTNET: NetworkParams = NetworkParams {
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0xc4,
+    bech32_hrp: "tb",
+    wif_prefix: 0xef,
+};
+
+#[cfg(feature = "bitcoin")]
+const REGTEST: NetworkParams = NetworkParams {
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0xc4,
+    bech32_hrp: "bcrt",
+    wif_prefix: 0xef,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Chain {
+    #[default]
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+#[cfg(feature = "bitcoin")]
+pub fn network_params(chain: Chain) -> &'static NetworkParams {
+    match chain {
+        Chain::Mainnet => &MAINNET,
+        // Signet shares the testnet prefixes and HRP
+        Chain::Testnet | Chain::Signet => &TESTNET,
+        Chain::Regtest => &REGTEST,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AddressType {
+    /// P2PKH of the 33-byte SEC1 compressed public key
+    #[default]
+    P2pkh,
+    /// Legacy P2PKH of the 65-byte uncompressed public key
+    P2pkhUncompressed,
+    /// Nested SegWit, P2WPKH wrapped into P2SH
+    P2shP2wpkh,
+    /// Native SegWit v0 (bech32)
+    P2wpkh,
+    /// Taproot key-path spend without a script tree (BIP86, bech32m)
+    P2tr,
+}
+
+impl AddressType {
+    /// The output buffer len (including the NUL terminator), bech32 addresses depend on the HRP len
+    pub fn address_len(self, params: &NetworkParams) -> usize {
+        match self {
+            Self::P2pkh | Self::P2pkhUncompressed | Self::P2shP2wpkh => ADDRESS_LEN,
+            Self::P2wpkh => params.bech32_hrp.len() + P2WPKH_DATA_LEN + 1,
+            Self::P2tr => params.bech32_hrp.len() + P2TR_DATA_LEN + 1,
+        }
+    }
+
+    /// Whether the address commits to the compressed public key, WIF keys carry the same flag
+    pub fn is_compressed(self) -> bool {
+        !matches!(self, Self::P2pkhUncompressed)
+    }
+}
+
+pub fn get_keypair(
+    address_type: AddressType,
+    params: &NetworkParams,
+) -> Result<KeypairDerived<MAX_ADDRESS_LEN_INTERNAL>> {
+    get_keypair_internal(Keypair::generate(), params, address_type)
 }
 
-pub(super) fn get_keypair_internal(version_byte: u8) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    let kp = Keypair::generate();
+pub fn keypair_from_secret(
+    secret_key: &[u8; 32],
+    address_type: AddressType,
+    params: &NetworkParams,
+) -> Result<KeypairDerived<MAX_ADDRESS_LEN_INTERNAL>> {
+    get_keypair_internal(Keypair::from_secret_key(secret_key)?, params, address_type)
+}
 
+fn get_keypair_internal(
+    kp: Keypair,
+    params: &NetworkParams,
+    address_type: AddressType,
+) -> Result<KeypairDerived<MAX_ADDRESS_LEN_INTERNAL>> {
     let secret_key = kp.secret_key();
-    let public_key = kp.public_key();
 
-    let mut address = [0; ADDRESS_LEN_INTERNAL];
+    let mut address = [0; MAX_ADDRESS_LEN_INTERNAL];
+
+    match address_type {
+        AddressType::P2pkh => {
+            let pk_hash = hash160(&kp.public_key_compressed());
+            base58_address(params.p2pkh_prefix, &pk_hash, &mut address)?;
+        },
+        AddressType::P2pkhUncompressed => {
+            let pk_hash = hash160(&kp.public_key());
+            base58_address(params.p2pkh_prefix, &pk_hash, &mut address)?;
+        },
+        AddressType::P2shP2wpkh => {
+            // The redeem script is a P2WPKH output script: OP_0 PUSH20 <pk_hash>
+            let mut redeem_script = [0; 22];
+            redeem_script[0] = 0x00;
+            redeem_script[1] = 0x14;
+            redeem_script[2..].copy_from_slice(&hash160(&kp.public_key_compressed()));
+
+            let script_hash = hash160(&redeem_script);
+            base58_address(params.p2sh_prefix, &script_hash, &mut address)?;
+        },
+        AddressType::P2wpkh => {
+            let pk_hash = hash160(&kp.public_key_compressed());
+            bech32::encode_segwit(params.bech32_hrp, 0, &pk_hash, &mut address)?;
+        },
+        AddressType::P2tr => {
+            let output_key = taproot_output_key(&kp)?;
+            bech32::encode_segwit(params.bech32_hrp, 1, &output_key, &mut address)?;
+        },
+    }
+
+    Ok(KeypairDerived::new(secret_key, address))
+}
+
+/// Encodes the secret key in the Wallet Import Format, returns the encoded buffer and its len
+pub fn encode_wif(
+    secret_key: &[u8; 32],
+    params: &NetworkParams,
+    compressed: bool,
+) -> Result<(Zeroizing<[u8; WIF_LEN_INTERNAL]>, usize)> {
+    let mut payload = Zeroizing::new([0; WIF_PAYLOAD_LEN]);
+    payload[0] = params.wif_prefix;
+    payload[1..33].copy_from_slice(secret_key);
+
+    let payload_len = if compressed {
+        payload[33] = 0x01;
+        WIF_PAYLOAD_LEN
+    } else {
+        WIF_PAYLOAD_LEN - 1
+    };
+
+    let mut output = Zeroizing::new([0; WIF_LEN_INTERNAL]);
+    let len = bs58_check::bs58_check(&mut payload[..payload_len], output.as_mut_slice())?;
+
+    Ok((output, len))
+}
+
+/// Decodes a Wallet Import Format key of the network, returns the secret key and the compression flag
+pub fn decode_wif(wif: &[u8], params: &NetworkParams) -> Result<(Zeroizing<[u8; 32]>, bool)> {
+    let mut payload = Zeroizing::new([0; super::decode_buffer_len(WIF_PAYLOAD_LEN)]);
+    let len = bs58_check::bs58_check_decode(wif, payload.as_mut_slice())?;
+
+    let compressed = match len {
+        33 => false,
+        34 if payload[33] == 0x01 => true,
+        _ => return Err(KsgenError::invalid_wif("unexpected len or compression flag".into())),
+    };
+
+    if payload[0] != params.wif_prefix {
+        let err = KsgenError::invalid_wif(format!(
+            "unexpected prefix (expected: 0x{:02x}, actual: 0x{:02x})",
+            params.wif_prefix, payload[0],
+        ));
+        return Err(err);
+    }
+
+    let mut secret_key = Zeroizing::new([0; 32]);
+    secret_key.copy_from_slice(&payload[1..33]);
+
+    Ok((secret_key, compressed))
+}
 
-    let mut pk_hash = [0; 33];
-    pk_hash[0] = version_byte;
+fn base58_address(version_byte: u8, hash: &[u8; 20], output: &mut [u8]) -> Result<()> {
+    // Version byte + 20 bytes of the hash + 4 bytes for the checksum
+    let mut payload = [0; 25];
+    payload[0] = version_byte;
+    payload[1..21].copy_from_slice(hash);
+
+    bs58_check::bs58_check(&mut payload, output)?;
+
+    Ok(())
+}
+
+// BIP341 output key committing to no script tree: Q = P + H_TapTweak(P)G
+fn taproot_output_key(kp: &Keypair) -> Result<[u8; 32]> {
+    let internal_key = kp.x_only_public_key();
+    let tweak = tagged_hash(b"TapTweak", &internal_key);
+
+    kp.tweak_x_only_public_key(&tweak)
+}
+
+// BIP340 tagged hash: SHA256(SHA256(tag) || SHA256(tag) || msg)
+fn tagged_hash(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+
+    let mut output = [0; 32];
+
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    hasher.update(msg);
+    hasher.finalize_into(output.as_mut_slice().into());
+
+    output
+}
+
+// RIPEMD160 of the SHA256 hash
+pub(super) fn hash160(input: &[u8]) -> [u8; 20] {
+    let mut sha256_hash = [0; 32];
 
     let mut hasher = Sha256::new();
-    hasher.update(&public_key);
-    hasher.finalize_into((&mut pk_hash[1..]).into());
+    hasher.update(input);
+    hasher.finalize_into(sha256_hash.as_mut_slice().into());
+
+    let mut output = [0; 20];
 
     let mut hasher = Ripemd160::new();
-    hasher.update(&pk_hash);
-    hasher.finalize_into((&mut pk_hash[1..21]).into());
+    hasher.update(sha256_hash);
+    hasher.finalize_into(output.as_mut_slice().into());
 
-    bs58_check::bs58_check(&mut pk_hash[..25], address.as_mut_slice())?;
+    output
+}
 
-    Ok(KeypairDerived::new(secret_key, address))
+// The Bitcoin network params are compiled out of Litecoin-only builds
+#[cfg(all(test, feature = "bitcoin"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypair_from_secret() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2pkh, &MAINNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2pkhUncompressed, &MAINNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2shP2wpkh, &MAINNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN");
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2wpkh, &MAINNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+    }
+
+    #[test]
+    fn test_wif() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let vectors = [
+            (&MAINNET, true, "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn"),
+            (&MAINNET, false, "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf"),
+            (&TESTNET, true, "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN87JcbXMTcA"),
+        ];
+
+        for (params, compressed, expected) in vectors {
+            let (wif, len) = encode_wif(&secret_key, params, compressed).unwrap();
+            assert_eq!(&wif[..len], expected.as_bytes());
+
+            let (decoded, decoded_compressed) = decode_wif(expected.as_bytes(), params).unwrap();
+            assert_eq!(*decoded, secret_key);
+            assert_eq!(decoded_compressed, compressed);
+        }
+
+        // Wrong network prefix and a corrupted checksum
+        assert!(decode_wif(b"KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn", &TESTNET).is_err());
+        assert!(decode_wif(b"KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWo", &MAINNET).is_err());
+    }
+
+    #[test]
+    fn test_network_params() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let params = network_params(Chain::Testnet);
+        let kp = keypair_from_secret(&secret_key, AddressType::P2pkh, params).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r");
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2wpkh, params).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx");
+
+        let params = network_params(Chain::Regtest);
+        let kp = keypair_from_secret(&secret_key, AddressType::P2wpkh, params).unwrap();
+        assert_eq!(kp.address_ref().unwrap().len() + 1, AddressType::P2wpkh.address_len(params));
+        assert!(kp.address_ref().unwrap().starts_with(b"bcrt1q"));
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2tr, params).unwrap();
+        assert_eq!(kp.address_ref().unwrap().len() + 1, AddressType::P2tr.address_len(params));
+    }
+
+    #[test]
+    fn test_p2tr() {
+        // BIP86 test vectors: m/86'/0'/0'/0/0 and m/86'/0'/0'/0/1
+        let vectors = [
+            (
+                "41f41d69260df4cf277826a9b65a3717e4eeddbeedf637f212ca096576479361",
+                "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+                "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c",
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+            (
+                "86c68ac0ed7df88cbdd08a847c6d639f87d1234d40503abf3ac178ef7ddc05dd",
+                "83dfe85a3151d2517290da461fe2815591ef69f2b18a2ce63f01697a8b313145",
+                "a82f29944d65b86ae6b5e5cc75e294ead6c59391a1edc5e016e3498c67fc7bbb",
+                "bc1p4qhjn9zdvkux4e44uhx8tc55attvtyu358kutcqkudyccelu0was9fqzwh",
+            ),
+        ];
+
+        for (secret_key, internal_key, output_key, address) in vectors {
+            let secret_key = hex::decode(secret_key).unwrap().try_into().unwrap();
+
+            let kp = Keypair::from_secret_key(&secret_key).unwrap();
+            assert_eq!(hex::encode(kp.x_only_public_key()), internal_key);
+            assert_eq!(hex::encode(taproot_output_key(&kp).unwrap()), output_key);
+
+            let kp = keypair_from_secret(&secret_key, AddressType::P2tr, &MAINNET).unwrap();
+            assert_eq!(kp.address_ref().unwrap(), address.as_bytes());
+        }
+    }
 }
\ No newline at end of file
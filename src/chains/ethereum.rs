@@ -1,13 +1,48 @@
+use std::io::Write;
+
 use sha3::{Digest, Keccak256};
 
 use crate::{error::Result, kp::{derived_kp::KeypairDerived, secp256k1_kp::Keypair}};
 
-const ADDRESS_LEN_INTERNAL: usize = 40;
-pub const ADDRESS_LEN: usize = ADDRESS_LEN_INTERNAL + 1;
+// Checksummed addresses are 0x-prefixed, plain lowercase ones are 40 hex chars
+const ADDRESS_LEN_INTERNAL: usize = 42;
+const ADDRESS_HEX_LEN: usize = 40;
+pub const ADDRESS_LEN: usize = ADDRESS_HEX_LEN + 1;
+pub const CHECKSUM_ADDRESS_LEN: usize = ADDRESS_LEN_INTERNAL + 1;
+
+// u64::MAX has 20 decimal digits
+const ADDRESS_PREFIX_MAX_LEN: usize = 20;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AddressFormat {
+    /// Plain lowercase hex without the 0x prefix
+    #[default]
+    Lowercase,
+    /// 0x-prefixed EIP-55 mixed-case checksum
+    Eip55,
+    /// 0x-prefixed EIP-1191 mixed-case checksum bound to the chain id (RSK-style networks)
+    Eip1191 { chain_id: u64 },
+}
 
-pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    let kp = Keypair::generate();
+impl AddressFormat {
+    /// The output buffer len (including the NUL terminator), checksum formats add the 0x prefix
+    pub fn address_len(self) -> usize {
+        match self {
+            Self::Lowercase => ADDRESS_LEN,
+            Self::Eip55 | Self::Eip1191 { .. } => CHECKSUM_ADDRESS_LEN,
+        }
+    }
+}
 
+pub fn get_keypair(format: AddressFormat) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    derive_keypair(Keypair::generate(), format)
+}
+
+pub fn keypair_from_secret(secret_key: &[u8; 32], format: AddressFormat) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    derive_keypair(Keypair::from_secret_key(secret_key)?, format)
+}
+
+fn derive_keypair(kp: Keypair, format: AddressFormat) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
     let secret_key = kp.secret_key();
     let public_key = kp.public_key();
 
@@ -18,7 +53,96 @@ pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
     hasher.update(&public_key[1..]);
     hasher.finalize_into(pk_hash.as_mut_slice().into());
 
-    hex::encode_to_slice(&pk_hash[12..], &mut address)?;
+    let chain_id = match format {
+        AddressFormat::Lowercase => {
+            hex::encode_to_slice(&pk_hash[12..], &mut address[..ADDRESS_HEX_LEN])?;
+            return Ok(KeypairDerived::new(secret_key, address));
+        },
+        AddressFormat::Eip55 => None,
+        AddressFormat::Eip1191 { chain_id } => Some(chain_id),
+    };
+
+    address[..2].copy_from_slice(b"0x");
+    hex::encode_to_slice(&pk_hash[12..], &mut address[2..])?;
+    to_checksum_address(&mut address[2..], chain_id)?;
 
     Ok(KeypairDerived::new(secret_key, address))
+}
+
+// Uppercases every letter whose nibble in the Keccak256 hash of the lowercase address is >= 8.
+// EIP-1191 prefixes the hashed address with the decimal chain id and "0x"
+fn to_checksum_address(address: &mut [u8], chain_id: Option<u64>) -> Result<()> {
+    let mut hash = [0; 32];
+
+    let mut hasher = Keccak256::new();
+    if let Some(chain_id) = chain_id {
+        let mut prefix = [0; ADDRESS_PREFIX_MAX_LEN];
+        let len = {
+            let mut cursor = prefix.as_mut_slice();
+            write!(cursor, "{}", chain_id)?;
+            ADDRESS_PREFIX_MAX_LEN - cursor.len()
+        };
+
+        hasher.update(&prefix[..len]);
+        hasher.update(b"0x");
+    }
+    hasher.update(&*address);
+    hasher.finalize_into(hash.as_mut_slice().into());
+
+    for (i, c) in address.iter_mut().enumerate() {
+        let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+        if nibble >= 8 {
+            c.make_ascii_uppercase();
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypair_from_secret() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let kp = keypair_from_secret(&secret_key, AddressFormat::Lowercase).unwrap();
+
+        assert_eq!(*kp.secret_key, secret_key);
+        assert_eq!(kp.address_ref().unwrap(), b"7e5f4552091a69125d5dfcb7b8c2659029395bdf");
+        assert!(keypair_from_secret(&[0; 32], AddressFormat::Lowercase).is_err());
+
+        let kp = keypair_from_secret(&secret_key, AddressFormat::Eip55).unwrap();
+        assert_eq!(kp.address_ref_fixed().unwrap(), b"0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+
+        let kp = keypair_from_secret(&secret_key, AddressFormat::Eip1191 { chain_id: 30 }).unwrap();
+        assert!(kp.address_ref().unwrap().starts_with(b"0x"));
+        assert_eq!(kp.address_ref().unwrap().len(), ADDRESS_LEN_INTERNAL);
+
+        assert_eq!(AddressFormat::Lowercase.address_len(), 41);
+        assert_eq!(AddressFormat::Eip1191 { chain_id: 30 }.address_len(), 43);
+    }
+
+    #[test]
+    fn test_to_checksum_address() {
+        // EIP-55 and EIP-1191 (chain ids 30 and 31) test vectors
+        let vectors = [
+            (None, "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            (None, "fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"),
+            (Some(30), "5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD"),
+            (Some(30), "Fb6916095cA1Df60bb79ce92cE3EA74c37c5d359"),
+            (Some(31), "5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd"),
+            (Some(31), "Fb6916095CA1dF60bb79CE92ce3Ea74C37c5D359"),
+        ];
+
+        for (chain_id, expected) in vectors {
+            let mut address = [0; ADDRESS_HEX_LEN];
+            address.copy_from_slice(expected.to_ascii_lowercase().as_bytes());
+
+            to_checksum_address(&mut address, chain_id).unwrap();
+            assert_eq!(address, expected.as_bytes());
+        }
+    }
 }
\ No newline at end of file
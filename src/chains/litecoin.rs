@@ -1,10 +1,93 @@
-use crate::{error::Result, kp::derived_kp::KeypairDerived};
+use crate::{error::{KsgenError, Result}, kp::derived_kp::KeypairDerived};
 
-use super::bitcoin;
+use super::bitcoin::{self, AddressType, Chain, NetworkParams};
 
-const ADDRESS_LEN_INTERNAL: usize = bitcoin::ADDRESS_LEN_INTERNAL;
-pub const ADDRESS_LEN: usize = bitcoin::ADDRESS_LEN;
+const ADDRESS_LEN_INTERNAL: usize = bitcoin::MAX_ADDRESS_LEN_INTERNAL;
 
-pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    bitcoin::get_keypair_internal(0x30)
+const MAINNET: NetworkParams = NetworkParams {
+    p2pkh_prefix: 0x30,
+    // M-addresses; 0x05 (3-addresses) is deprecated for Litecoin
+    p2sh_prefix: 0x32,
+    bech32_hrp: "ltc",
+    wif_prefix: 0xb0,
+};
+
+const TESTNET: NetworkParams = NetworkParams {
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0x3a,
+    bech32_hrp: "tltc",
+    wif_prefix: 0xef,
+};
+
+const REGTEST: NetworkParams = NetworkParams {
+    p2pkh_prefix: 0x6f,
+    p2sh_prefix: 0x3a,
+    bech32_hrp: "rltc",
+    wif_prefix: 0xef,
+};
+
+pub fn network_params(chain: Chain) -> Result<&'static NetworkParams> {
+    match chain {
+        Chain::Mainnet => Ok(&MAINNET),
+        Chain::Testnet => Ok(&TESTNET),
+        Chain::Regtest => Ok(&REGTEST),
+        Chain::Signet => Err(KsgenError::unsupported_chain("Litecoin".into(), format!("{:?}", chain))),
+    }
+}
+
+pub fn get_keypair(
+    address_type: AddressType,
+    params: &NetworkParams,
+) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    bitcoin::get_keypair(address_type, params)
+}
+
+pub fn keypair_from_secret(
+    secret_key: &[u8; 32],
+    address_type: AddressType,
+    params: &NetworkParams,
+) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    bitcoin::keypair_from_secret(secret_key, address_type, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypair_from_secret() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2pkh, &MAINNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ");
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2shP2wpkh, &MAINNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"MR8UQSBr5ULwWheBHznrHk2jxyxkHQu8vB");
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2wpkh, &MAINNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9");
+        assert_eq!(kp.address_ref().unwrap().len() + 1, AddressType::P2wpkh.address_len(&MAINNET));
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2wpkh, &TESTNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"tltc1qw508d6qejxtdg4y5r3zarvary0c5xw7klfsuq0");
+
+        let kp = keypair_from_secret(&secret_key, AddressType::P2pkh, &TESTNET).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r");
+
+        assert!(network_params(Chain::Signet).is_err());
+    }
+
+    #[test]
+    fn test_wif() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let (wif, len) = bitcoin::encode_wif(&secret_key, &MAINNET, true).unwrap();
+        assert_eq!(&wif[..len], b"T33ydQRKp4FCW5LCLLUB7deioUMoveiwekdwUwyfRDeGZm76aUjV");
+
+        let (decoded, compressed) = bitcoin::decode_wif(&wif[..len], &MAINNET).unwrap();
+        assert_eq!(*decoded, secret_key);
+        assert!(compressed);
+    }
 }
\ No newline at end of file
@@ -17,4 +17,11 @@ pub mod solana;
 pub mod sui;
 
 #[cfg(feature = "tron")]
-pub mod tron;
\ No newline at end of file
+pub mod tron;
+
+/// Decode buffer len of a `len`-byte payload. One spare byte, so longer inputs fail on the len
+/// check instead of the buffer size
+#[cfg(any(feature = "bitcoin", feature = "litecoin", feature = "solana", feature = "sui"))]
+pub(crate) const fn decode_buffer_len(len: usize) -> usize {
+    len + 1
+}
\ No newline at end of file
@@ -1,11 +1,45 @@
-use crate::{error::Result, kp::{derived_kp::KeypairDerived, ed25519_kp::Keypair}};
+use std::io::Write;
+
+use zeroize::Zeroizing;
+
+use crate::{
+    error::{KsgenError, Result},
+    hd::{path, HARDENED},
+    keystore_ffi::{AddressKind, Chain, Network},
+    kp::{derived_kp::KeypairDerived, ed25519_kp::Keypair},
+};
 
 const ADDRESS_LEN_INTERNAL: usize = 44;
 pub const ADDRESS_LEN: usize = ADDRESS_LEN_INTERNAL + 1;
 
+// The seed followed by the public key, as solana-keygen stores it
+const KEYPAIR_LEN: usize = 64;
+
+// Base58 of 64 bytes takes at most 88 characters
+pub const KEYPAIR_BS58_LEN_INTERNAL: usize = 88;
+
+// "[" + 64 numbers of up to 3 digits + 63 commas + "]"
+pub const KEYPAIR_JSON_LEN_INTERNAL: usize = 2 + KEYPAIR_LEN * 3 + KEYPAIR_LEN - 1;
+
+pub const DERIVATION_PATH_LEN: usize = 4;
+
+/// The Phantom and solana-keygen path `m/44'/501'/i'/0'` of the account index
+pub fn derivation_path(index: u32) -> [u32; DERIVATION_PATH_LEN] {
+    let purpose = path::purpose(&Network::Solana, AddressKind::Default);
+    let coin_type = path::coin_type(&Network::Solana, Chain::Mainnet);
+
+    [HARDENED | purpose, HARDENED | coin_type, HARDENED | index, HARDENED]
+}
+
 pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    let kp = Keypair::generate();
+    derive_keypair(Keypair::generate())
+}
+
+pub fn keypair_from_secret(secret_key: &[u8; 32]) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    derive_keypair(Keypair::from_secret_key(secret_key))
+}
 
+fn derive_keypair(kp: Keypair) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
     let secret_key = kp.secret_key();
     let public_key = kp.public_key();
 
@@ -14,4 +48,157 @@ pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
         .onto(address.as_mut_slice())?;
 
     Ok(KeypairDerived::new(secret_key, address))
+}
+
+/// Encodes the 64-byte keypair in Base58 (Phantom import), returns the encoded buffer and its len
+pub fn export_keypair_bs58(secret_key: &[u8; 32]) -> Result<(Zeroizing<[u8; KEYPAIR_BS58_LEN_INTERNAL]>, usize)> {
+    let keypair = keypair_bytes(secret_key);
+
+    let mut output = Zeroizing::new([0; KEYPAIR_BS58_LEN_INTERNAL]);
+    let len = bs58::encode(keypair.as_slice())
+        .onto(output.as_mut_slice())?;
+
+    Ok((output, len))
+}
+
+/// Writes the 64-byte keypair as a JSON byte array (solana-keygen `id.json`), returns the buffer and its len
+pub fn export_keypair_json(secret_key: &[u8; 32]) -> Result<(Zeroizing<[u8; KEYPAIR_JSON_LEN_INTERNAL]>, usize)> {
+    let keypair = keypair_bytes(secret_key);
+
+    let mut output = Zeroizing::new([0; KEYPAIR_JSON_LEN_INTERNAL]);
+    let len = {
+        let mut cursor = output.as_mut_slice();
+
+        cursor.write_all(b"[")?;
+        for (i, b) in keypair.iter().enumerate() {
+            if i > 0 {
+                cursor.write_all(b",")?;
+            }
+            write!(cursor, "{}", b)?;
+        }
+        cursor.write_all(b"]")?;
+
+        KEYPAIR_JSON_LEN_INTERNAL - cursor.len()
+    };
+
+    Ok((output, len))
+}
+
+/// Decodes a Base58 64-byte keypair and returns its seed
+pub fn import_keypair_bs58(input: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    let mut keypair = Zeroizing::new([0; super::decode_buffer_len(KEYPAIR_LEN)]);
+    let len = bs58::decode(input)
+        .onto(keypair.as_mut_slice())?;
+
+    if len != KEYPAIR_LEN {
+        return Err(KsgenError::invalid_keypair(format!("expected {} bytes, got {}", KEYPAIR_LEN, len)));
+    }
+
+    seed_from_keypair(&keypair[..KEYPAIR_LEN])
+}
+
+/// Parses a JSON byte array of the 64-byte keypair and returns its seed
+pub fn import_keypair_json(input: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    let mut keypair = Zeroizing::new([0; KEYPAIR_LEN]);
+    parse_json_bytes(input, keypair.as_mut_slice())?;
+
+    seed_from_keypair(keypair.as_slice())
+}
+
+fn keypair_bytes(secret_key: &[u8; 32]) -> Zeroizing<[u8; KEYPAIR_LEN]> {
+    let kp = Keypair::from_secret_key(secret_key);
+
+    let mut keypair = Zeroizing::new([0; KEYPAIR_LEN]);
+    keypair[..32].copy_from_slice(secret_key);
+    keypair[32..].copy_from_slice(&kp.public_key());
+
+    keypair
+}
+
+// Rejects keypairs whose embedded public key doesn't belong to the seed
+fn seed_from_keypair(keypair: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    let mut seed = Zeroizing::new([0; 32]);
+    seed.copy_from_slice(&keypair[..32]);
+
+    if Keypair::from_secret_key(&seed).public_key() != keypair[32..] {
+        return Err(KsgenError::invalid_keypair("the public key doesn't match the seed".into()));
+    }
+
+    Ok(seed)
+}
+
+// A strict `[n, n, ...]` parser that fills the whole output without allocations
+fn parse_json_bytes(input: &[u8], output: &mut [u8]) -> Result<()> {
+    let invalid = || KsgenError::invalid_keypair("expected a JSON array of 64 bytes".into());
+
+    let input = input.trim_ascii();
+    let input = input.strip_prefix(b"[")
+        .and_then(|x| x.strip_suffix(b"]"))
+        .ok_or_else(invalid)?;
+
+    let mut items = input.split(|x| x == &b',');
+    for o in output.iter_mut() {
+        let item = items.next().ok_or_else(invalid)?.trim_ascii();
+        if item.is_empty() || item.len() > 3 || !item.iter().all(u8::is_ascii_digit) {
+            return Err(invalid());
+        }
+
+        let value = item.iter().fold(0u16, |acc, x| acc * 10 + (x - b'0') as u16);
+        *o = u8::try_from(value).map_err(|_| invalid())?;
+    }
+
+    if items.next().is_some() {
+        return Err(invalid());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hd::slip10, mnemonic};
+
+    use super::*;
+
+    #[test]
+    fn test_derivation_path() {
+        let phrase = b"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let seed = mnemonic::to_seed(phrase, b"").unwrap();
+
+        let secret_key = slip10::derive_keypair(seed.as_slice(), &derivation_path(0)).unwrap().secret_key();
+        let kp = keypair_from_secret(&secret_key).unwrap();
+        assert_eq!(kp.address_ref().unwrap(), b"HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+    }
+
+    #[test]
+    fn test_keypair_export() {
+        let secret_key = [0; 32];
+
+        let (bs58, len) = export_keypair_bs58(&secret_key).unwrap();
+        assert_eq!(
+            &bs58[..len],
+            b"111111111111111111111111111111114zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS",
+        );
+        assert_eq!(*import_keypair_bs58(&bs58[..len]).unwrap(), secret_key);
+
+        let (json, len) = export_keypair_json(&[0xff; 32]).unwrap();
+        assert!(json[..len].starts_with(b"[255,255,"));
+        assert_eq!(*import_keypair_json(&json[..len]).unwrap(), [0xff; 32]);
+
+        // The longest possible output fits the buffer
+        let (_, len) = export_keypair_json(&[100; 32]).unwrap();
+        assert!(len <= KEYPAIR_JSON_LEN_INTERNAL);
+    }
+
+    #[test]
+    fn test_keypair_import_rejects_mismatch() {
+        let (mut json, len) = export_keypair_json(&[1; 32]).unwrap();
+        let input = std::str::from_utf8(&json[..len]).unwrap().replacen("[1,", "[2,", 1);
+        assert!(import_keypair_json(input.as_bytes()).is_err());
+
+        json[len - 1] = b',';
+        assert!(import_keypair_json(&json[..len]).is_err());
+        assert!(import_keypair_json(b"[256]").is_err());
+        assert!(import_keypair_bs58(b"4zvwRjXUKGfvwnParsHAS3HuSVzV5cA4McphgmoCtajS").is_err());
+    }
 }
\ No newline at end of file
@@ -1,30 +1,316 @@
+use base64ct::{Base64, Encoding};
 use blake2::{Blake2b, digest::consts::U32, Digest};
+use zeroize::Zeroizing;
 
-use crate::{error::Result, kp::{derived_kp::KeypairDerived, ed25519_kp::Keypair}};
+#[cfg(feature = "sui-secp256r1")]
+use crate::kp::p256_kp;
+
+use crate::{
+    bech32,
+    error::{KsgenError, Result},
+    hd::{path, HARDENED},
+    keystore_ffi::{AddressKind, Chain, Network},
+    kp::{derived_kp::KeypairDerived, ed25519_kp, secp256k1_kp},
+};
 
 const ADDRESS_LEN_INTERNAL: usize = 64;
 pub const ADDRESS_LEN: usize = ADDRESS_LEN_INTERNAL + 1;
 
+// The scheme flag followed by the 32-byte key
+const PRIVATE_KEY_LEN: usize = 33;
+const PRIVATE_KEY_HRP: &str = "suiprivkey";
+
+// "suiprivkey" + "1" + 53 groups of 5 bits + 6 checksum characters
+pub const PRIVATE_KEY_BECH32_LEN_INTERNAL: usize = 70;
+pub const PRIVATE_KEY_BASE64_LEN_INTERNAL: usize = 44;
+
 type Blake2b32 = Blake2b<U32>;
 
-pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    let kp = Keypair::generate();
+/// The flag byte Sui prepends to public and private keys
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SignatureScheme {
+    #[default]
+    Ed25519 = 0x00,
+    Secp256k1 = 0x01,
+    #[cfg(feature = "sui-secp256r1")]
+    Secp256r1 = 0x02,
+}
+
+impl SignatureScheme {
+    pub fn from_flag(flag: u8) -> Result<Self> {
+        match flag {
+            0x00 => Ok(Self::Ed25519),
+            0x01 => Ok(Self::Secp256k1),
+            #[cfg(feature = "sui-secp256r1")]
+            0x02 => Ok(Self::Secp256r1),
+            _ => Err(KsgenError::unsupported_signature_scheme("Sui".into(), flag)),
+        }
+    }
+
+    #[inline]
+    pub fn flag(self) -> u8 {
+        self as u8
+    }
+}
+
+pub const DERIVATION_PATH_LEN: usize = 5;
+
+/// The Sui Wallet ed25519 path `m/44'/784'/0'/0'/i'` of the address index
+pub fn derivation_path(index: u32) -> [u32; DERIVATION_PATH_LEN] {
+    let purpose = path::purpose(&Network::Sui, AddressKind::Default);
+    let coin_type = path::coin_type(&Network::Sui, Chain::Mainnet);
 
-    let secret_key = kp.secret_key();
-    let public_key = kp.public_key();
+    [HARDENED | purpose, HARDENED | coin_type, HARDENED, HARDENED, HARDENED | index]
+}
 
+pub fn get_keypair(scheme: SignatureScheme) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    match scheme {
+        SignatureScheme::Ed25519 => {
+            let kp = ed25519_kp::Keypair::generate();
+            derive_keypair(kp.secret_key(), scheme, &kp.public_key())
+        },
+        SignatureScheme::Secp256k1 => {
+            let kp = secp256k1_kp::Keypair::generate();
+            derive_keypair(kp.secret_key(), scheme, &kp.public_key_compressed())
+        },
+        #[cfg(feature = "sui-secp256r1")]
+        SignatureScheme::Secp256r1 => {
+            let kp = p256_kp::Keypair::generate();
+            derive_keypair(kp.secret_key(), scheme, &kp.public_key_compressed())
+        },
+    }
+}
+
+pub fn keypair_from_secret(secret_key: &[u8; 32], scheme: SignatureScheme) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    match scheme {
+        SignatureScheme::Ed25519 => {
+            let kp = ed25519_kp::Keypair::from_secret_key(secret_key);
+            derive_keypair(kp.secret_key(), scheme, &kp.public_key())
+        },
+        SignatureScheme::Secp256k1 => {
+            let kp = secp256k1_kp::Keypair::from_secret_key(secret_key)?;
+            derive_keypair(kp.secret_key(), scheme, &kp.public_key_compressed())
+        },
+        #[cfg(feature = "sui-secp256r1")]
+        SignatureScheme::Secp256r1 => {
+            let kp = p256_kp::Keypair::from_secret_key(secret_key)?;
+            derive_keypair(kp.secret_key(), scheme, &kp.public_key_compressed())
+        },
+    }
+}
+
+/// Keystores don't record the signature scheme, so it's recovered from the address the key was
+/// created with (64 hex chars, the 0x prefix and the case are ignored)
+pub fn signature_scheme_of(secret_key: &[u8; 32], address: &[u8]) -> Result<SignatureScheme> {
+    let address = address.strip_prefix(b"0x").unwrap_or(address);
+
+    let schemes = (0..=u8::MAX).map_while(|flag| SignatureScheme::from_flag(flag).ok());
+    for scheme in schemes {
+        // A secret key out of range of the curve can't belong to the scheme
+        let Ok(kp) = keypair_from_secret(secret_key, scheme) else {
+            continue;
+        };
+        if kp.address_ref()?.eq_ignore_ascii_case(address) {
+            return Ok(scheme);
+        }
+    }
+
+    Err(KsgenError::invalid_address("no signature scheme of the key derives the address".into()))
+}
+
+// The address is the Blake2b-256 hash of the flag followed by the public key
+// (32 bytes for ed25519, 33 compressed bytes for the ECDSA schemes)
+fn derive_keypair(
+    secret_key: Zeroizing<[u8; 32]>,
+    scheme: SignatureScheme,
+    public_key: &[u8],
+) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
     let mut address = [0; ADDRESS_LEN_INTERNAL];
 
-    // In-place hashing of the public key with starting version byte (0x00)
-    address[1..33].copy_from_slice(&public_key);
+    // In-place hashing of the public key with starting flag byte
+    let pk_len = 1 + public_key.len();
+    address[0] = scheme.flag();
+    address[1..pk_len].copy_from_slice(public_key);
 
     let mut pk_hash = [0; 32];
 
     let mut hasher = Blake2b32::new();
-    hasher.update(&address[..33]);
+    hasher.update(&address[..pk_len]);
     hasher.finalize_into(pk_hash.as_mut_slice().into());
 
     hex::encode_to_slice(pk_hash, &mut address)?;
 
     Ok(KeypairDerived::new(secret_key, address))
+}
+
+/// Encodes the key as the Bech32 `suiprivkey1...` string of the Sui CLI and wallets
+pub fn export_private_key_bech32(
+    secret_key: &[u8; 32],
+    scheme: SignatureScheme,
+) -> Result<Zeroizing<[u8; PRIVATE_KEY_BECH32_LEN_INTERNAL]>> {
+    let private_key = private_key_bytes(secret_key, scheme);
+
+    let mut output = Zeroizing::new([0; PRIVATE_KEY_BECH32_LEN_INTERNAL]);
+    bech32::encode(PRIVATE_KEY_HRP, private_key.as_slice(), output.as_mut_slice())?;
+
+    Ok(output)
+}
+
+/// Encodes the key in Base64 as the entries of the `sui.keystore` file
+pub fn export_private_key_base64(
+    secret_key: &[u8; 32],
+    scheme: SignatureScheme,
+) -> Result<Zeroizing<[u8; PRIVATE_KEY_BASE64_LEN_INTERNAL]>> {
+    let private_key = private_key_bytes(secret_key, scheme);
+
+    let mut output = Zeroizing::new([0; PRIVATE_KEY_BASE64_LEN_INTERNAL]);
+    Base64::encode(private_key.as_slice(), output.as_mut_slice())
+        .map_err(base64ct::Error::from)?;
+
+    Ok(output)
+}
+
+/// Decodes a `suiprivkey1...` string, returns the key and its signature scheme
+pub fn import_private_key_bech32(input: &[u8]) -> Result<(Zeroizing<[u8; 32]>, SignatureScheme)> {
+    let mut private_key = Zeroizing::new([0; PRIVATE_KEY_LEN]);
+    let len = bech32::decode(input, PRIVATE_KEY_HRP, private_key.as_mut_slice())?;
+
+    split_private_key(&private_key[..len])
+}
+
+/// Decodes a `sui.keystore` entry, returns the key and its signature scheme
+pub fn import_private_key_base64(input: &[u8]) -> Result<(Zeroizing<[u8; 32]>, SignatureScheme)> {
+    let mut private_key = Zeroizing::new([0; super::decode_buffer_len(PRIVATE_KEY_LEN)]);
+    let len = Base64::decode(input, private_key.as_mut_slice())?.len();
+
+    split_private_key(&private_key[..len])
+}
+
+fn private_key_bytes(secret_key: &[u8; 32], scheme: SignatureScheme) -> Zeroizing<[u8; PRIVATE_KEY_LEN]> {
+    let mut private_key = Zeroizing::new([0; PRIVATE_KEY_LEN]);
+    private_key[0] = scheme.flag();
+    private_key[1..].copy_from_slice(secret_key);
+
+    private_key
+}
+
+fn split_private_key(private_key: &[u8]) -> Result<(Zeroizing<[u8; 32]>, SignatureScheme)> {
+    if private_key.len() != PRIVATE_KEY_LEN {
+        return Err(KsgenError::invalid_keypair(format!(
+            "expected the flag and a 32-byte key ({} bytes), got {} bytes",
+            PRIVATE_KEY_LEN,
+            private_key.len(),
+        )));
+    }
+
+    let scheme = SignatureScheme::from_flag(private_key[0])?;
+
+    let mut secret_key = Zeroizing::new([0; 32]);
+    secret_key.copy_from_slice(&private_key[1..]);
+
+    Ok((secret_key, scheme))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{hd::{bip32, slip10}, mnemonic};
+
+    use super::*;
+
+    #[test]
+    fn test_derivation_path() {
+        let phrase = b"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let seed = mnemonic::to_seed(phrase, b"").unwrap();
+
+        let secret_key = slip10::derive_keypair(seed.as_slice(), &derivation_path(0)).unwrap().secret_key();
+        let kp = keypair_from_secret(&secret_key, SignatureScheme::Ed25519).unwrap();
+        assert_eq!(kp.address_ref_fixed().unwrap(), b"5e93a736d04fbb25737aa40bee40171ef79f65fae833749e3c089fe7cc2161f1");
+
+        // Sui secp256k1 keys are derived with BIP32
+        let phrase = b"film crazy soon outside stand loop subway crumble thrive popular green nuclear struggle pistol arm wife phrase warfare march wheat nephew ask sunny firm";
+        let seed = mnemonic::to_seed(phrase, b"").unwrap();
+
+        let path = [54 | HARDENED, 784 | HARDENED, HARDENED, 0, 0];
+        let secret_key = bip32::derive_keypair(seed.as_slice(), &path).unwrap().secret_key();
+        let kp = keypair_from_secret(&secret_key, SignatureScheme::Secp256k1).unwrap();
+        assert_eq!(kp.address_ref_fixed().unwrap(), b"9e8f732575cc5386f8df3c784cd3ed1b53ce538da79926b2ad54dcc1197d2532");
+    }
+
+    #[test]
+    fn test_private_key_export() {
+        let secret_key = [0; 32];
+
+        let bech32 = export_private_key_bech32(&secret_key, SignatureScheme::Ed25519).unwrap();
+        assert_eq!(
+            bech32.as_slice(),
+            b"suiprivkey1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq509duq",
+        );
+        let (imported, scheme) = import_private_key_bech32(bech32.as_slice()).unwrap();
+        assert_eq!((*imported, scheme), (secret_key, SignatureScheme::Ed25519));
+
+        let base64 = export_private_key_base64(&[0xff; 32], SignatureScheme::Ed25519).unwrap();
+        assert_eq!(base64.as_slice(), b"AP//////////////////////////////////////////");
+        let (imported, _) = import_private_key_base64(base64.as_slice()).unwrap();
+        assert_eq!(*imported, [0xff; 32]);
+    }
+
+    #[test]
+    fn test_keypair_from_secret() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let kp = keypair_from_secret(&secret_key, SignatureScheme::Secp256k1).unwrap();
+        assert_eq!(kp.address_ref_fixed().unwrap(), b"d4c3524e6642b2e54945c02378024f822ac3f80b0870a5f95f06e68a61890a6c");
+
+        #[cfg(feature = "sui-secp256r1")]
+        {
+            let kp = keypair_from_secret(&secret_key, SignatureScheme::Secp256r1).unwrap();
+            assert_eq!(kp.address_ref_fixed().unwrap(), b"173e0d2ec575814f055dee0c3c0ce1357c9f3d58a8019b04dd369ca263f6db55");
+        }
+
+        // Out of the secp256k1 curve order
+        assert!(keypair_from_secret(&[0; 32], SignatureScheme::Secp256k1).is_err());
+    }
+
+    #[test]
+    fn test_signature_scheme_of() {
+        let mut secret_key = [0; 32];
+        secret_key[31] = 1;
+
+        let address = b"0xD4C3524E6642B2E54945C02378024F822AC3F80B0870A5F95F06E68A61890A6C";
+        assert_eq!(signature_scheme_of(&secret_key, address).unwrap(), SignatureScheme::Secp256k1);
+
+        let kp = keypair_from_secret(&secret_key, SignatureScheme::Ed25519).unwrap();
+        let address = kp.address_ref().unwrap();
+        assert_eq!(signature_scheme_of(&secret_key, address).unwrap(), SignatureScheme::Ed25519);
+
+        #[cfg(feature = "sui-secp256r1")]
+        {
+            let address = b"173e0d2ec575814f055dee0c3c0ce1357c9f3d58a8019b04dd369ca263f6db55";
+            assert_eq!(signature_scheme_of(&secret_key, address).unwrap(), SignatureScheme::Secp256r1);
+        }
+
+        let err = signature_scheme_of(&[2; 32], address).err().unwrap();
+        assert!(matches!(err, KsgenError::InvalidAddressErr { .. }));
+    }
+
+    #[test]
+    fn test_private_key_import_rejects_unsupported() {
+        let input = b"AQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAB";
+        let (_, scheme) = import_private_key_base64(input).unwrap();
+        assert_eq!(scheme, SignatureScheme::Secp256k1);
+
+        // Unknown flag (0x03)
+        let input = b"AwAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        assert!(matches!(
+            import_private_key_base64(input),
+            Err(KsgenError::UnsupportedSignatureSchemeErr { flag: 0x03, .. }),
+        ));
+
+        assert!(matches!(
+            import_private_key_base64(b"AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"),
+            Err(KsgenError::InvalidKeypairErr { .. }),
+        ));
+        assert!(import_private_key_bech32(b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").is_err());
+    }
 }
\ No newline at end of file
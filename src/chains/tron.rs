@@ -6,8 +6,14 @@ const ADDRESS_LEN_INTERNAL: usize = 34;
 pub const ADDRESS_LEN: usize = ADDRESS_LEN_INTERNAL + 1;
 
 pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
-    let kp = Keypair::generate();
+    derive_keypair(Keypair::generate())
+}
 
+pub fn keypair_from_secret(secret_key: &[u8; 32]) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
+    derive_keypair(Keypair::from_secret_key(secret_key)?)
+}
+
+fn derive_keypair(kp: Keypair) -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
     let secret_key = kp.secret_key();
     let public_key = kp.public_key();
 
@@ -19,7 +25,7 @@ pub fn get_keypair() -> Result<KeypairDerived<ADDRESS_LEN_INTERNAL>> {
     pk_hash[0] = 0x41;
 
     let mut hasher = Keccak256::new();
-    hasher.update(&public_key);
+    hasher.update(public_key);
     hasher.finalize_into((&mut pk_hash[1..]).into());
 
     // Put the last 20 bytes of a pk hash to 1..21 indexes
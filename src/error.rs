@@ -7,6 +7,20 @@ pub type Result<T> = std::result::Result<T, KsgenError>;
 #[cfg(any(feature = "bitcoin", feature = "litecoin", feature = "solana", feature = "tron"))]
 use bs58::encode::Error as Bs58Error;
 
+#[cfg(any(feature = "bitcoin", feature = "litecoin", feature = "solana", feature = "tron"))]
+use bs58::decode::Error as Bs58DecodeError;
+
+#[cfg(feature = "sui")]
+use base64ct::Error as Base64Error;
+
+#[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "sui", feature = "tron"))]
+use secp256k1::Error as Secp256k1Error;
+
+#[cfg(feature = "sui-secp256r1")]
+use p256::elliptic_curve::Error as P256Error;
+
+// Every variant keeps the `Err` suffix of the original error names
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum KsgenError {
     #[error("err scrypt hashing: {0}")]
@@ -18,9 +32,122 @@ pub enum KsgenError {
     #[error("err scrypt invalid params: {0}")]
     ScryptInvalidParamsErr(#[from] InvalidParams),
 
+    #[error("err writing output: {0}")]
+    IoErr(#[from] std::io::Error),
+
     #[error("err encoding to Hex: {0}")]
     FromHexErr(#[from] FromHexError),
 
+    #[error("err decoding from Hex: (field name: {field_name:}): {source:}")]
+    DecodeHexErr {
+        field_name: String,
+        source: FromHexError,
+    },
+
+    #[error("err unsupported cipher: (expected: {expected:}, actual: {actual:})")]
+    UnsupportedCipherErr {
+        expected: String,
+        actual: String,
+    },
+
+    #[error("err unsupported kdf: (expected: {expected:}, actual: {actual:})")]
+    UnsupportedKdfErr {
+        expected: String,
+        actual: String,
+    },
+
+    #[error("err unsupported prf: (expected: {expected:}, actual: {actual:})")]
+    UnsupportedPrfErr {
+        expected: String,
+        actual: String,
+    },
+
+    #[error("err invalid kdf param: (field name: {field_name:}, reason: {reason:})")]
+    InvalidKdfParamErr {
+        field_name: String,
+        reason: String,
+    },
+
+    #[error("err parsing JSON: {0}")]
+    JsonErr(#[from] serde_json::Error),
+
+    #[error("err unsupported keystore version: (expected: {expected:}, actual: {actual:})")]
+    UnsupportedVersionErr {
+        expected: u8,
+        actual: u8,
+    },
+
+    #[error("err unsupported address kind: (network: {network:}, kind: {kind:})")]
+    UnsupportedAddressKindErr {
+        network: String,
+        kind: String,
+    },
+
+    #[error("err unsupported chain: (network: {network:}, chain: {chain:})")]
+    UnsupportedChainErr {
+        network: String,
+        chain: String,
+    },
+
+    #[error("err invalid chain id: (network: {network:}, chain id: {chain_id:})")]
+    InvalidChainIdErr {
+        network: String,
+        chain_id: u64,
+    },
+
+    #[error("err invalid checksum")]
+    InvalidChecksumErr,
+
+    #[error("err invalid WIF: {reason:}")]
+    InvalidWifErr {
+        reason: String,
+    },
+
+    #[error("err invalid keypair: {reason:}")]
+    InvalidKeypairErr {
+        reason: String,
+    },
+
+    #[error("err invalid mnemonic: {reason:}")]
+    InvalidMnemonicErr {
+        reason: String,
+    },
+
+    #[error("err invalid derivation: {reason:}")]
+    InvalidDerivationErr {
+        reason: String,
+    },
+
+    #[error("err invalid bech32: {reason:}")]
+    InvalidBech32Err {
+        reason: String,
+    },
+
+    #[error("err unsupported signature scheme: (network: {network:}, flag: {flag:#04x})")]
+    UnsupportedSignatureSchemeErr {
+        network: String,
+        flag: u8,
+    },
+
+    #[error("err invalid plaintext: {reason:}")]
+    InvalidPlaintextErr {
+        reason: String,
+    },
+
+    #[error("err unexpected plaintext kind: (expected: {expected:}, actual: {actual:})")]
+    UnexpectedPlaintextKindErr {
+        expected: String,
+        actual: String,
+    },
+
+    #[error("err invalid address: {reason:}")]
+    InvalidAddressErr {
+        reason: String,
+    },
+
+    #[error("err invalid mac: the password is wrong or the keystore is corrupted")]
+    InvalidMacErr,
+
     #[error("err invalid output len: (expected: {input_len:}, actual: {output_len:})")]
     InvalidOutputLenErr {
         input_len: usize,
@@ -33,9 +160,31 @@ pub enum KsgenError {
         field_name: String,
     },
 
+    #[error("err invalid enum value: (field name: {field_name:}, value: {value:})")]
+    InvalidEnumValueErr {
+        field_name: String,
+        value: u32,
+    },
+
     #[cfg(any(feature = "bitcoin", feature = "litecoin", feature = "solana", feature = "tron"))]
     #[error("err encoding to Base58: {0}")]
     Bs58Err(#[from] Bs58Error),
+
+    #[cfg(any(feature = "bitcoin", feature = "litecoin", feature = "solana", feature = "tron"))]
+    #[error("err decoding from Base58: {0}")]
+    Bs58DecodeErr(#[from] Bs58DecodeError),
+
+    #[cfg(feature = "sui")]
+    #[error("err decoding from Base64: {0}")]
+    Base64Err(#[from] Base64Error),
+
+    #[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "sui", feature = "tron"))]
+    #[error("err secp256k1: {0}")]
+    Secp256k1Err(#[from] Secp256k1Error),
+
+    #[cfg(feature = "sui-secp256r1")]
+    #[error("err secp256r1: {0}")]
+    P256Err(#[from] P256Error),
 }
 
 impl KsgenError {
@@ -50,4 +199,80 @@ impl KsgenError {
     pub fn null_ptr(field_name: String) -> Self {
         Self::NullPtrErr { field_name }
     }
+
+    pub fn invalid_enum_value(field_name: String, value: u32) -> Self {
+        Self::InvalidEnumValueErr { field_name, value }
+    }
+
+    pub fn decode_hex(field_name: String, source: FromHexError) -> Self {
+        Self::DecodeHexErr { field_name, source }
+    }
+
+    pub fn unsupported_cipher(expected: String, actual: String) -> Self {
+        Self::UnsupportedCipherErr { expected, actual }
+    }
+
+    pub fn unsupported_kdf(expected: String, actual: String) -> Self {
+        Self::UnsupportedKdfErr { expected, actual }
+    }
+
+    pub fn unsupported_prf(expected: String, actual: String) -> Self {
+        Self::UnsupportedPrfErr { expected, actual }
+    }
+
+    pub fn unsupported_version(expected: u8, actual: u8) -> Self {
+        Self::UnsupportedVersionErr { expected, actual }
+    }
+
+    pub fn invalid_kdf_param(field_name: String, reason: String) -> Self {
+        Self::InvalidKdfParamErr { field_name, reason }
+    }
+
+    pub fn unsupported_address_kind(network: String, kind: String) -> Self {
+        Self::UnsupportedAddressKindErr { network, kind }
+    }
+
+    pub fn unsupported_chain(network: String, chain: String) -> Self {
+        Self::UnsupportedChainErr { network, chain }
+    }
+
+    pub fn invalid_chain_id(network: String, chain_id: u64) -> Self {
+        Self::InvalidChainIdErr { network, chain_id }
+    }
+
+    pub fn invalid_wif(reason: String) -> Self {
+        Self::InvalidWifErr { reason }
+    }
+
+    pub fn invalid_keypair(reason: String) -> Self {
+        Self::InvalidKeypairErr { reason }
+    }
+
+    pub fn invalid_mnemonic(reason: String) -> Self {
+        Self::InvalidMnemonicErr { reason }
+    }
+
+    pub fn invalid_derivation(reason: String) -> Self {
+        Self::InvalidDerivationErr { reason }
+    }
+
+    pub fn invalid_bech32(reason: String) -> Self {
+        Self::InvalidBech32Err { reason }
+    }
+
+    pub fn unsupported_signature_scheme(network: String, flag: u8) -> Self {
+        Self::UnsupportedSignatureSchemeErr { network, flag }
+    }
+
+    pub fn invalid_plaintext(reason: String) -> Self {
+        Self::InvalidPlaintextErr { reason }
+    }
+
+    pub fn unexpected_plaintext_kind(expected: String, actual: String) -> Self {
+        Self::UnexpectedPlaintextKindErr { expected, actual }
+    }
+
+    pub fn invalid_address(reason: String) -> Self {
+        Self::InvalidAddressErr { reason }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+use crate::{error::{KsgenError, Result}, kp::secp256k1_kp::Keypair};
+
+use super::{HARDENED, MAX_DEPTH};
+
+const MASTER_KEY: &[u8] = b"Bitcoin seed";
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// A BIP32 extended private key
+pub struct ExtendedKey {
+    keypair: Keypair,
+    chain_code: Zeroizing<[u8; 32]>,
+}
+
+impl ExtendedKey {
+    /// Derives the master key from a 16 to 64-byte seed
+    pub fn master(seed: &[u8]) -> Result<Self> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err(KsgenError::invalid_derivation(format!("unsupported seed len ({})", seed.len())));
+        }
+
+        let mut mac = HmacSha512::new_from_slice(MASTER_KEY).expect("HMAC accepts keys of any len");
+        mac.update(seed);
+
+        Self::from_hmac(mac, None)
+    }
+
+    /// Derives a child key, hardened when the index has the `HARDENED` bit set
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        let mut mac = HmacSha512::new_from_slice(self.chain_code.as_slice()).expect("HMAC accepts keys of any len");
+
+        if index & HARDENED != 0 {
+            mac.update(&[0]);
+            mac.update(self.keypair.secret_key().as_slice());
+        } else {
+            mac.update(&self.keypair.public_key_compressed());
+        }
+        mac.update(&index.to_be_bytes());
+
+        Self::from_hmac(mac, Some(&self.keypair))
+    }
+
+    /// Derives the key at the path of child indexes starting from the master key
+    pub fn derive_path(seed: &[u8], path: &[u32]) -> Result<Self> {
+        if path.len() > MAX_DEPTH {
+            return Err(KsgenError::invalid_derivation(format!("the path is deeper than {}", MAX_DEPTH)));
+        }
+
+        path.iter().try_fold(Self::master(seed)?, |key, &index| key.derive_child(index))
+    }
+
+    #[inline]
+    pub fn into_keypair(self) -> Keypair {
+        self.keypair
+    }
+
+    // The left half is the key (or the tweak of the parent key), the right half is the chain code.
+    // Keys out of the curve order are rejected instead of skipping to the next index
+    fn from_hmac(mac: HmacSha512, parent: Option<&Keypair>) -> Result<Self> {
+        let output = Zeroizing::new(<[u8; 64]>::from(mac.finalize().into_bytes()));
+
+        let mut key = Zeroizing::new([0; 32]);
+        key.copy_from_slice(&output[..32]);
+
+        let keypair = match parent {
+            Some(parent) => parent.add_tweak(&key)?,
+            None => Keypair::from_secret_key(&key)?,
+        };
+
+        let mut chain_code = Zeroizing::new([0; 32]);
+        chain_code.copy_from_slice(&output[32..]);
+
+        Ok(Self { keypair, chain_code })
+    }
+}
+
+/// Derives the secp256k1 keypair at the path from a BIP39 seed
+pub fn derive_keypair(seed: &[u8], path: &[u32]) -> Result<Keypair> {
+    Ok(ExtendedKey::derive_path(seed, path)?.into_keypair())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_1() {
+        // BIP32 test vector 1
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+
+        let master = ExtendedKey::master(&seed).unwrap();
+        assert_eq!(hex::encode(master.keypair.secret_key()), "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35");
+        assert_eq!(hex::encode(master.chain_code), "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508");
+
+        let vectors = [
+            (vec![HARDENED], "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea"),
+            (vec![HARDENED, 1], "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368"),
+            (vec![HARDENED, 1, HARDENED | 2], "cbce0d719ecf7431d88e6a89fa1483e02e35092af60c042b1df2ff59fa424dca"),
+            (vec![HARDENED, 1, HARDENED | 2, 2], "0f479245fb19a38a1954c5c7c0ebab2f9bdfd96a17563ef28a6a4b1a2a764ef4"),
+            (vec![HARDENED, 1, HARDENED | 2, 2, 1000000000], "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8"),
+        ];
+
+        for (path, secret_key) in vectors {
+            let kp = derive_keypair(&seed, &path).unwrap();
+            assert_eq!(hex::encode(kp.secret_key()), secret_key);
+        }
+
+        let key = ExtendedKey::derive_path(&seed, &[HARDENED, 1]).unwrap();
+        assert_eq!(hex::encode(key.chain_code), "2a7857631386ba23dacac34180dd1983734e444fdbf774041578e9b6adb37c19");
+    }
+
+    #[test]
+    fn test_invalid_seed() {
+        assert!(ExtendedKey::master(&[0; 15]).is_err());
+        assert!(ExtendedKey::master(&[0; 65]).is_err());
+        assert!(derive_keypair(&[0; 16], &[0; MAX_DEPTH + 1]).is_err());
+    }
+}
\ No newline at end of file
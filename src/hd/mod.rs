@@ -0,0 +1,13 @@
+/// Set on the index of hardened derivation steps
+pub const HARDENED: u32 = 0x8000_0000;
+
+// BIP32 serializes the depth as a single byte
+pub const MAX_DEPTH: usize = 255;
+
+pub mod path;
+
+#[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "sui", feature = "tron"))]
+pub mod bip32;
+
+#[cfg(any(feature = "aptos", feature = "solana", feature = "sui"))]
+pub mod slip10;
\ No newline at end of file
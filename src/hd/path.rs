@@ -0,0 +1,171 @@
+use crate::{error::{KsgenError, Result}, keystore_ffi::{AddressKind, AddressOptionsInternal, Chain, Network}};
+
+use super::{HARDENED, MAX_DEPTH};
+
+// SLIP-44 registers coin type 1 for the test networks of all coins
+const TESTNET_COIN_TYPE: u32 = 1;
+
+/// A parsed `m/84'/0'/0'/0/5` path, hardened segments are marked with `'`, `h` or `H`
+pub struct DerivationPathInternal {
+    indexes: [u32; MAX_DEPTH],
+    len: usize,
+}
+
+impl DerivationPathInternal {
+    pub fn parse(input: &[u8]) -> Result<Self> {
+        let input = input.trim_ascii();
+        let segments = input.strip_prefix(b"m")
+            .ok_or_else(|| KsgenError::invalid_derivation("the path must start with m".into()))?;
+
+        let mut path = Self { indexes: [0; MAX_DEPTH], len: 0 };
+        if segments.is_empty() {
+            return Ok(path);
+        }
+
+        let segments = segments.strip_prefix(b"/")
+            .ok_or_else(|| KsgenError::invalid_derivation("segments must be separated by /".into()))?;
+
+        for segment in segments.split(|x| x == &b'/') {
+            if path.len == MAX_DEPTH {
+                return Err(KsgenError::invalid_derivation(format!("the path is deeper than {}", MAX_DEPTH)));
+            }
+
+            path.indexes[path.len] = parse_segment(segment)?;
+            path.len += 1;
+        }
+
+        Ok(path)
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[u32] {
+        &self.indexes[..self.len]
+    }
+
+    /// Ed25519 keys (SLIP-0010) accept only hardened segments, Sui secp256k1 (BIP32) paths end with
+    /// non-hardened ones. Sui secp256r1 keys can't be derived
+    pub fn validate(&self, network: &Network, address_options: &AddressOptionsInternal) -> Result<()> {
+        let hardened_only = match network {
+            Network::Aptos | Network::Solana => true,
+            Network::Sui => match address_options.kind {
+                AddressKind::Secp256k1 => false,
+                AddressKind::Default => true,
+                kind => {
+                    let reason = format!("the network ({:?}) doesn't support derivation for {:?}", network, kind);
+                    return Err(KsgenError::invalid_derivation(reason));
+                },
+            },
+            _ => false,
+        };
+
+        match self.as_slice().iter().position(|x| x & HARDENED == 0) {
+            Some(i) if hardened_only => {
+                let reason = format!(
+                    "the network ({:?}) supports only hardened segments for {:?} (position {})",
+                    network,
+                    address_options.kind,
+                    i + 1,
+                );
+                Err(KsgenError::invalid_derivation(reason))
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+fn parse_segment(segment: &[u8]) -> Result<u32> {
+    let invalid = || KsgenError::invalid_derivation(format!("invalid segment ({})", String::from_utf8_lossy(segment)));
+
+    let (digits, hardened) = match segment.split_last() {
+        Some((b'\'' | b'h' | b'H', digits)) => (digits, HARDENED),
+        _ => (segment, 0),
+    };
+
+    if digits.is_empty() || digits.len() > 10 || !digits.iter().all(u8::is_ascii_digit) {
+        return Err(invalid());
+    }
+
+    let index = digits.iter().fold(0u64, |acc, x| acc * 10 + (x - b'0') as u64);
+    if index >= HARDENED as u64 {
+        return Err(invalid());
+    }
+
+    Ok(index as u32 | hardened)
+}
+
+/// The BIP43 purpose of the address kind: 44 (P2PKH and account-based networks), 49 (P2SH-P2WPKH),
+/// 84 (P2WPKH) and 86 (P2TR). Sui secp256k1 and secp256r1 accounts use 54 and 74
+pub fn purpose(network: &Network, kind: AddressKind) -> u32 {
+    match (network, kind) {
+        (Network::Bitcoin | Network::Litecoin, AddressKind::P2shP2wpkh) => 49,
+        (Network::Bitcoin | Network::Litecoin, AddressKind::P2wpkh) => 84,
+        (Network::Bitcoin, AddressKind::P2tr) => 86,
+        (Network::Sui, AddressKind::Secp256k1) => 54,
+        (Network::Sui, AddressKind::Secp256r1) => 74,
+        _ => 44,
+    }
+}
+
+/// The registered SLIP-44 coin type of the network
+pub fn coin_type(network: &Network, chain: Chain) -> u32 {
+    if !matches!(chain, Chain::Mainnet) {
+        return TESTNET_COIN_TYPE;
+    }
+
+    match network {
+        Network::Aptos => 637,
+        Network::Bitcoin => 0,
+        Network::Ethereum => 60,
+        Network::Litecoin => 2,
+        Network::Solana => 501,
+        Network::Sui => 784,
+        Network::Tron => 195,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let path = DerivationPathInternal::parse(b"m/84'/0'/0'/0/5").unwrap();
+        assert_eq!(path.as_slice(), [HARDENED | 84, HARDENED, HARDENED, 0, 5]);
+
+        let path = DerivationPathInternal::parse(b" m/44h/501H/2147483647'/0' ").unwrap();
+        assert_eq!(path.as_slice(), [HARDENED | 44, HARDENED | 501, u32::MAX, HARDENED]);
+
+        assert!(DerivationPathInternal::parse(b"m").unwrap().as_slice().is_empty());
+
+        for input in ["84'/0'", "m/", "m//0", "m0", "m/2147483648", "m/0''", "m/-1", "m/+1", "m/0x1", "M/0"] {
+            assert!(DerivationPathInternal::parse(input.as_bytes()).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let options = |kind| AddressOptionsInternal { kind, chain: Chain::Mainnet, chain_id: 0 };
+
+        let path = DerivationPathInternal::parse(b"m/44'/501'/0'/0").unwrap();
+        assert!(path.validate(&Network::Ethereum, &options(AddressKind::Default)).is_ok());
+        assert!(path.validate(&Network::Solana, &options(AddressKind::Default)).is_err());
+
+        let path = DerivationPathInternal::parse(b"m/44'/501'/0'/0'").unwrap();
+        assert!(path.validate(&Network::Solana, &options(AddressKind::Default)).is_ok());
+
+        let path = DerivationPathInternal::parse(b"m/54'/784'/0'/0/0").unwrap();
+        assert!(path.validate(&Network::Sui, &options(AddressKind::Secp256k1)).is_ok());
+        assert!(path.validate(&Network::Sui, &options(AddressKind::Default)).is_err());
+        assert!(path.validate(&Network::Sui, &options(AddressKind::Secp256r1)).is_err());
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(purpose(&Network::Bitcoin, AddressKind::P2tr), 86);
+        assert_eq!(purpose(&Network::Litecoin, AddressKind::P2tr), 44);
+        assert_eq!(purpose(&Network::Ethereum, AddressKind::Eip55), 44);
+
+        assert_eq!(coin_type(&Network::Tron, Chain::Mainnet), 195);
+        assert_eq!(coin_type(&Network::Litecoin, Chain::Testnet), 1);
+    }
+}
\ No newline at end of file
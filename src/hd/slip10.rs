@@ -0,0 +1,117 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+use crate::{error::{KsgenError, Result}, kp::ed25519_kp::Keypair};
+
+use super::{HARDENED, MAX_DEPTH};
+
+const MASTER_KEY: &[u8] = b"ed25519 seed";
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// A SLIP-0010 ed25519 extended private key, only hardened children can be derived
+pub struct ExtendedKey {
+    secret_key: Zeroizing<[u8; 32]>,
+    chain_code: Zeroizing<[u8; 32]>,
+}
+
+impl ExtendedKey {
+    /// Derives the master key from a 16 to 64-byte seed
+    pub fn master(seed: &[u8]) -> Result<Self> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err(KsgenError::invalid_derivation(format!("unsupported seed len ({})", seed.len())));
+        }
+
+        let mut mac = HmacSha512::new_from_slice(MASTER_KEY).expect("HMAC accepts keys of any len");
+        mac.update(seed);
+
+        Ok(Self::from_hmac(mac))
+    }
+
+    /// Derives a hardened child key, the index must have the `HARDENED` bit set
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        if index & HARDENED == 0 {
+            return Err(KsgenError::invalid_derivation(format!("ed25519 supports only hardened indexes ({})", index)));
+        }
+
+        let mut mac = HmacSha512::new_from_slice(self.chain_code.as_slice()).expect("HMAC accepts keys of any len");
+        mac.update(&[0]);
+        mac.update(self.secret_key.as_slice());
+        mac.update(&index.to_be_bytes());
+
+        Ok(Self::from_hmac(mac))
+    }
+
+    /// Derives the key at the path of child indexes starting from the master key
+    pub fn derive_path(seed: &[u8], path: &[u32]) -> Result<Self> {
+        if path.len() > MAX_DEPTH {
+            return Err(KsgenError::invalid_derivation(format!("the path is deeper than {}", MAX_DEPTH)));
+        }
+
+        path.iter().try_fold(Self::master(seed)?, |key, &index| key.derive_child(index))
+    }
+
+    #[inline]
+    pub fn keypair(&self) -> Keypair {
+        Keypair::from_secret_key(&self.secret_key)
+    }
+
+    // Any 32 bytes are a valid ed25519 seed, so unlike BIP32 every index produces a key
+    fn from_hmac(mac: HmacSha512) -> Self {
+        let output = Zeroizing::new(<[u8; 64]>::from(mac.finalize().into_bytes()));
+
+        let mut secret_key = Zeroizing::new([0; 32]);
+        secret_key.copy_from_slice(&output[..32]);
+
+        let mut chain_code = Zeroizing::new([0; 32]);
+        chain_code.copy_from_slice(&output[32..]);
+
+        Self { secret_key, chain_code }
+    }
+}
+
+/// Derives the ed25519 keypair at the hardened-only path from a BIP39 seed
+pub fn derive_keypair(seed: &[u8], path: &[u32]) -> Result<Keypair> {
+    Ok(ExtendedKey::derive_path(seed, path)?.keypair())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_1() {
+        // SLIP-0010 ed25519 test vector 1
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+
+        let master = ExtendedKey::master(&seed).unwrap();
+        assert_eq!(hex::encode(master.secret_key), "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7");
+        assert_eq!(hex::encode(master.chain_code), "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb");
+
+        let vectors = [
+            (vec![HARDENED], "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"),
+            (vec![HARDENED, HARDENED | 1], "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2"),
+            (vec![HARDENED, HARDENED | 1, HARDENED | 2], "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9"),
+            (vec![HARDENED, HARDENED | 1, HARDENED | 2, HARDENED | 2], "30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662"),
+            (
+                vec![HARDENED, HARDENED | 1, HARDENED | 2, HARDENED | 2, HARDENED | 1000000000],
+                "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            ),
+        ];
+
+        for (path, secret_key) in vectors {
+            let kp = derive_keypair(&seed, &path).unwrap();
+            assert_eq!(hex::encode(kp.secret_key()), secret_key);
+        }
+
+        let key = ExtendedKey::derive_path(&seed, &[HARDENED]).unwrap();
+        assert_eq!(hex::encode(key.chain_code), "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69");
+    }
+
+    #[test]
+    fn test_rejects_non_hardened() {
+        let seed = [0; 16];
+        assert!(derive_keypair(&seed, &[HARDENED, 1]).is_err());
+    }
+}
\ No newline at end of file
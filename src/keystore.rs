@@ -1,75 +1,179 @@
 use ctr::cipher::{KeyIvInit, StreamCipher};
 use rand::{rngs::OsRng, Rng};
 use scrypt::{password_hash::SaltString, Params};
+use sha2::Sha256;
 use sha3::{Digest, Keccak256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
 
-use crate::error::Result;
+use crate::{error::{KsgenError, Result}, keystore_ffi::PlaintextKind, mnemonic};
 
-const SCRYPT_DK_LEN: usize = 32;
+pub const CIPHER: &str = "aes-128-ctr";
+pub const SCRYPT: &str = "scrypt";
+pub const PBKDF2: &str = "pbkdf2";
+pub const PBKDF2_PRF: &str = "hmac-sha256";
+
+const DK_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+pub const MAX_SALT_LEN: usize = 32;
+// The longest plaintext is a 24-word phrase
+pub const MAX_PLAINTEXT_LEN: usize = mnemonic::MAX_PHRASE_LEN;
 const SCRYPT_N: u8 = 13;
 const SCRYPT_R: u32 = 8;
 const SCRYPT_P: u32 = 8;
+// geth's "light" and "standard" scrypt parameters
+const SCRYPT_LIGHT_N: u8 = 12;
+const SCRYPT_LIGHT_P: u32 = 6;
+const SCRYPT_STANDARD_N: u8 = 18;
+const SCRYPT_STANDARD_P: u32 = 1;
+const PBKDF2_C: u32 = 262_144;
+// Upper bounds for imported keystores, so a tiny document can't make the KDF
+// allocate or run without limits
+const MAX_SCRYPT_LOG_N: u8 = 20;
+const MAX_SCRYPT_MEMORY: u64 = 1 << 30;
+const MAX_SCRYPT_COST: u64 = 1 << 24;
+const MAX_PBKDF2_C: u32 = 10_000_000;
 
 type Aes128Ctr128BE = ctr::Ctr128BE<aes::Aes128>;
 
 pub struct KeystoreInternal<'a> {
     pub cipher: &'a str,
     pub cipherparams: CipherParams,
-    ciphertext: [u8; 32],
+    pub plaintext_kind: PlaintextKind,
+    ciphertext: [u8; MAX_PLAINTEXT_LEN],
+    ciphertext_len: usize,
     pub kdf: &'a str,
     pub kdfparams: KdfParamsInternal,
     mac: [u8; 32],
 }
 
 impl<'a> KeystoreInternal<'a> {
-    pub fn from_secret_key(mut secret_key: [u8; 32], password: &[u8]) -> Result<Self> {
-        let kdfparams = KdfParamsInternal::new()?;
-        let scrypt_params = kdfparams.as_scrypt_params()?;
+    pub fn from_secret_key(secret_key: Zeroizing<[u8; 32]>, password: &[u8], kdf: KdfInternal) -> Result<Self> {
+        Self::from_plaintext(PlaintextKind::SecretKey, secret_key.as_slice(), password, kdf)
+    }
 
-        // Hashes password bytes with a Scrypt function
-        let mut password_hash = [0u8; 32];
-        scrypt::scrypt(
-            password,
-            &kdfparams.salt,
-            &scrypt_params,
-            &mut password_hash
-        )?;
+    pub fn from_plaintext(plaintext_kind: PlaintextKind, plaintext: &[u8], password: &[u8], kdf: KdfInternal) -> Result<Self> {
+        plaintext_kind.validate_len(plaintext.len())?;
+
+        let kdfparams = KdfParamsInternal::new(kdf)?;
+        let password_hash = kdfparams.derive_key(password)?;
 
         let (encryption_key, mac_key) = password_hash.split_at(16);
 
-        // Encrypts secret key with an AES-128-CTR and initial vector
+        // Encrypts the plaintext in place with an AES-128-CTR and initial vector
         let cipherparams = CipherParams::new();
         let mut cipher = Aes128Ctr128BE::new(
             encryption_key.into(),
             &cipherparams.iv.into(),
         );
-        cipher.apply_keystream(&mut secret_key);
+        let mut ciphertext = Zeroizing::new([0; MAX_PLAINTEXT_LEN]);
+        let ciphertext = &mut ciphertext[..plaintext.len()];
+        ciphertext.copy_from_slice(plaintext);
+        cipher.apply_keystream(ciphertext);
 
-        // Hashes message authentication code with a Keccak256 function
-        // of the second-leftmost 16 bytes of the derived key together
-        // with the full ciphertext
-        let mut mac_base = [0; 48];
-        mac_base[..16].copy_from_slice(mac_key);
-        mac_base[16..].copy_from_slice(&secret_key);
-        let mut mac_hasher = Keccak256::new();
-        mac_hasher.update(&mac_base);
+        let mac = compute_mac(mac_key, ciphertext);
+
+        Self::new(cipherparams, plaintext_kind, ciphertext, kdfparams, mac)
+    }
 
-        let mut mac = [0; 32];
-        mac_hasher.finalize_into(mac.as_mut_slice().into());
+    pub fn new(
+        cipherparams: CipherParams,
+        plaintext_kind: PlaintextKind,
+        ciphertext: &[u8],
+        kdfparams: KdfParamsInternal,
+        mac: [u8; 32],
+    ) -> Result<Self> {
+        // AES-CTR keeps the plaintext len
+        plaintext_kind.validate_len(ciphertext.len())?;
 
-        let ciphertext = secret_key;
+        let mut ciphertext_buf = [0; MAX_PLAINTEXT_LEN];
+        ciphertext_buf[..ciphertext.len()].copy_from_slice(ciphertext);
 
         Ok(Self {
-            cipher: "aes-128-ctr",
-            cipherparams, ciphertext,
-            kdf: "scrypt",
+            cipher: CIPHER,
+            cipherparams, plaintext_kind,
+            ciphertext: ciphertext_buf,
+            ciphertext_len: ciphertext.len(),
+            kdf: kdfparams.kdf.name(),
             kdfparams, mac
         })
     }
 
     #[inline]
-    pub fn ciphertext_hex_encoded(&self) -> Result<[u8; 64]> {
-        to_hex(&self.ciphertext)
+    fn ciphertext(&self) -> &[u8] {
+        &self.ciphertext[..self.ciphertext_len]
+    }
+
+    /// Decrypts the plaintext of any kind
+    pub fn decrypt(&self, password: &[u8]) -> Result<Plaintext> {
+        let password_hash = self.kdfparams.derive_key(password)?;
+
+        let (encryption_key, mac_key) = password_hash.split_at(16);
+
+        // Compares MACs before decryption, so a wrong password never yields a garbage key
+        if !self.verify_mac(mac_key) {
+            return Err(KsgenError::InvalidMacErr);
+        }
+
+        let mut plaintext = Plaintext {
+            kind: self.plaintext_kind,
+            buf: Zeroizing::new(self.ciphertext),
+            len: self.ciphertext_len,
+        };
+        let mut cipher = Aes128Ctr128BE::new(
+            encryption_key.into(),
+            &self.cipherparams.iv.into(),
+        );
+        cipher.apply_keystream(&mut plaintext.buf[..plaintext.len]);
+
+        Ok(plaintext)
+    }
+
+    /// Decrypts the secret key, fails before running the KDF if the keystore holds another kind
+    pub fn decrypt_secret_key(&self, password: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        if self.plaintext_kind != PlaintextKind::SecretKey {
+            let err = KsgenError::unexpected_plaintext_kind(
+                format!("{:?}", PlaintextKind::SecretKey),
+                format!("{:?}", self.plaintext_kind),
+            );
+            return Err(err);
+        }
+
+        let plaintext = self.decrypt(password)?;
+        let mut secret_key = Zeroizing::new([0; 32]);
+        secret_key.copy_from_slice(plaintext.as_slice());
+
+        Ok(secret_key)
+    }
+
+    /// Checks the password against the MAC without decrypting the ciphertext
+    pub fn verify_password(&self, password: &[u8]) -> Result<bool> {
+        let password_hash = self.kdfparams.derive_key(password)?;
+
+        Ok(self.verify_mac(&password_hash[16..]))
+    }
+
+    #[inline]
+    fn verify_mac(&self, mac_key: &[u8]) -> bool {
+        compute_mac(mac_key, self.ciphertext()).ct_eq(&self.mac).into()
+    }
+
+    /// Verifies the old password and encrypts the plaintext again with a fresh salt and IV,
+    /// keeping the current KDF parameters unless new ones are passed
+    pub fn reencrypt(&self, old_password: &[u8], new_password: &[u8], kdf: Option<KdfInternal>) -> Result<Self> {
+        let plaintext = self.decrypt(old_password)?;
+        let kdf = kdf.unwrap_or(self.kdfparams.kdf);
+
+        Self::from_plaintext(plaintext.kind, plaintext.as_slice(), new_password, kdf)
+    }
+
+    /// Returns the hex buffer together with the len of its encoded part
+    #[inline]
+    pub fn ciphertext_hex_encoded(&self) -> Result<([u8; MAX_PLAINTEXT_LEN * 2], usize)> {
+        let len = self.ciphertext_len * 2;
+        let mut output = [0; MAX_PLAINTEXT_LEN * 2];
+        hex::encode_to_slice(self.ciphertext(), &mut output[..len])?;
+        Ok((output, len))
     }
 
     #[inline]
@@ -78,6 +182,20 @@ impl<'a> KeystoreInternal<'a> {
     }
 }
 
+/// A decrypted secret key, BIP39 entropy or phrase
+pub struct Plaintext {
+    pub kind: PlaintextKind,
+    buf: Zeroizing<[u8; MAX_PLAINTEXT_LEN]>,
+    len: usize,
+}
+
+impl Plaintext {
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
 pub struct CipherParams {
     iv: [u8; 16],
 }
@@ -88,56 +206,335 @@ impl CipherParams {
         Self { iv }
     }
 
+    pub fn from_iv(iv: [u8; 16]) -> Self {
+        Self { iv }
+    }
+
     #[inline]
     pub fn iv_hex_encoded(&self) -> Result<[u8; 32]> {
         to_hex(&self.iv)
     }
 }
 
+/// Key derivation function together with its cost parameters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KdfInternal {
+    /// `n` is a log₂ of the iterations count
+    Scrypt { n: u8, r: u32, p: u32 },
+    /// PBKDF2-HMAC-SHA256 with `c` iterations
+    Pbkdf2 { c: u32 },
+}
+
+impl KdfInternal {
+    pub fn scrypt() -> Self {
+        Self::Scrypt { n: SCRYPT_N, r: SCRYPT_R, p: SCRYPT_P }
+    }
+
+    pub fn pbkdf2() -> Self {
+        Self::Pbkdf2 { c: PBKDF2_C }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Scrypt { .. } => SCRYPT,
+            Self::Pbkdf2 { .. } => PBKDF2,
+        }
+    }
+
+    fn derive_key(&self, password: &[u8], salt: &[u8], dklen: usize, output: &mut [u8]) -> Result<()> {
+        match *self {
+            Self::Scrypt { n, r, p } => {
+                let scrypt_params = Params::new(n, r, p, dklen)?;
+                scrypt::scrypt(password, salt, &scrypt_params, output)?;
+            },
+            Self::Pbkdf2 { c } => {
+                if c == 0 {
+                    return Err(KsgenError::invalid_kdf_param("c".into(), "must be greater than 0".into()));
+                }
+                pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, c, output);
+            },
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for KdfInternal {
+    fn default() -> Self {
+        Self::scrypt()
+    }
+}
+
+/// Builds validated scrypt parameters starting from the defaults or a preset
+pub struct ScryptBuilder {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl ScryptBuilder {
+    pub fn new() -> Self {
+        Self { log_n: SCRYPT_N, r: SCRYPT_R, p: SCRYPT_P }
+    }
+
+    /// n = 2^12, r = 8, p = 6
+    pub fn light() -> Self {
+        Self { log_n: SCRYPT_LIGHT_N, r: SCRYPT_R, p: SCRYPT_LIGHT_P }
+    }
+
+    /// n = 2^18, r = 8, p = 1
+    pub fn standard() -> Self {
+        Self { log_n: SCRYPT_STANDARD_N, r: SCRYPT_R, p: SCRYPT_STANDARD_P }
+    }
+
+    pub fn log_n(mut self, log_n: u8) -> Self {
+        self.log_n = log_n;
+        self
+    }
+
+    pub fn r(mut self, r: u32) -> Self {
+        self.r = r;
+        self
+    }
+
+    pub fn p(mut self, p: u32) -> Self {
+        self.p = p;
+        self
+    }
+
+    pub fn build(self) -> Result<KdfInternal> {
+        let Self { log_n, r, p } = self;
+
+        if log_n == 0 {
+            return Err(KsgenError::invalid_kdf_param("log_n".into(), "must be greater than 0".into()));
+        }
+        if r == 0 {
+            return Err(KsgenError::invalid_kdf_param("r".into(), "must be greater than 0".into()));
+        }
+        if p == 0 {
+            return Err(KsgenError::invalid_kdf_param("p".into(), "must be greater than 0".into()));
+        }
+        // Required by scrypt: n < 2^(128 * r / 8)
+        if u64::from(log_n) >= u64::from(r) * 16 {
+            let err = KsgenError::invalid_kdf_param(
+                "log_n".into(),
+                format!("{} must be less than r * 16 ({})", log_n, u64::from(r) * 16),
+            );
+            return Err(err);
+        }
+
+        if log_n > MAX_SCRYPT_LOG_N {
+            let err = KsgenError::invalid_kdf_param(
+                "log_n".into(),
+                format!("{} is greater than {}", log_n, MAX_SCRYPT_LOG_N),
+            );
+            return Err(err);
+        }
+        // scrypt allocates 128 * r * (n + p) bytes and runs n * r * p block mixes
+        let n = 1u64 << log_n;
+        let (r64, p64) = (u64::from(r), u64::from(p));
+        if 128 * r64 * (n + p64) > MAX_SCRYPT_MEMORY {
+            let err = KsgenError::invalid_kdf_param(
+                "log_n, r, p".into(),
+                format!("n = 2^{}, r = {}, p = {} need more than {} bytes of memory", log_n, r, p, MAX_SCRYPT_MEMORY),
+            );
+            return Err(err);
+        }
+        if n * r64 * p64 > MAX_SCRYPT_COST {
+            let err = KsgenError::invalid_kdf_param(
+                "log_n, r, p".into(),
+                format!("n * r * p = {} is greater than {}", n * r64 * p64, MAX_SCRYPT_COST),
+            );
+            return Err(err);
+        }
+
+        // Catches the remaining overflows of n * r * 128 and p * r * 128
+        Params::new(log_n, r, p, DK_LEN).map_err(|_| {
+            KsgenError::invalid_kdf_param(
+                "log_n, r, p".into(),
+                format!("n = 2^{}, r = {}, p = {} overflow the memory bounds", log_n, r, p),
+            )
+        })?;
+
+        Ok(KdfInternal::Scrypt { n: log_n, r, p })
+    }
+}
+
+impl Default for ScryptBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct KdfParamsInternal {
     pub dklen: usize,
-    pub n: u8,
-    pub r: u32,
-    pub p: u32,
-    salt: [u8; 16],
+    pub kdf: KdfInternal,
+    salt: [u8; MAX_SALT_LEN],
+    salt_len: usize,
 }
 
 impl KdfParamsInternal {
-    pub fn new() -> Result<Self> {
+    pub fn new(kdf: KdfInternal) -> Result<Self> {
         let salt_string = SaltString::generate(&mut OsRng);
-        let mut salt = [0u8; 16];
-        salt_string.decode_b64(&mut salt)?;
+        let mut salt = [0u8; MAX_SALT_LEN];
+        salt_string.decode_b64(&mut salt[..SALT_LEN])?;
 
         Ok(Self {
-            dklen: SCRYPT_DK_LEN,
-            n: SCRYPT_N,
-            r: SCRYPT_R,
-            p: SCRYPT_P,
-            salt
+            dklen: DK_LEN,
+            kdf,
+            salt,
+            salt_len: SALT_LEN,
         })
     }
 
-    pub fn as_scrypt_params(&self) -> Result<Params> {
-        Ok(Params::new(
-            self.n,
-            self.r,
-            self.p,
-            self.dklen,
-        )?)
+    pub fn from_parts(dklen: usize, kdf: KdfInternal, salt: &[u8]) -> Result<Self> {
+        // The AES-128 key and the MAC key are the two halves of the 32-byte derived key
+        if dklen != DK_LEN {
+            let err = KsgenError::invalid_kdf_param(
+                "dklen".into(),
+                format!("{} bytes, expected {} bytes", dklen, DK_LEN),
+            );
+            return Err(err);
+        }
+
+        // geth and most wallets use 32-byte salts, ksgen generates 16-byte ones
+        if salt.len() > MAX_SALT_LEN {
+            let err = KsgenError::invalid_kdf_param(
+                "salt".into(),
+                format!("{} bytes is longer than {} bytes", salt.len(), MAX_SALT_LEN),
+            );
+            return Err(err);
+        }
+
+        let mut salt_buf = [0; MAX_SALT_LEN];
+        salt_buf[..salt.len()].copy_from_slice(salt);
+
+        Ok(Self { dklen, kdf, salt: salt_buf, salt_len: salt.len() })
+    }
+
+    #[inline]
+    pub fn salt(&self) -> &[u8] {
+        &self.salt[..self.salt_len]
+    }
+
+    // Hashes password bytes with the selected KDF
+    fn derive_key(&self, password: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        let mut password_hash = Zeroizing::new([0u8; 32]);
+        self.kdf.derive_key(password, self.salt(), self.dklen, password_hash.as_mut_slice())?;
+
+        Ok(password_hash)
     }
 
+    /// Returns the hex buffer together with the len of its encoded part
     #[inline]
-    pub fn salt_hex_encoded(&self) -> Result<[u8; 32]> {
-        to_hex(&self.salt)
+    pub fn salt_hex_encoded(&self) -> Result<([u8; MAX_SALT_LEN * 2], usize)> {
+        let len = self.salt_len * 2;
+        let mut output = [0; MAX_SALT_LEN * 2];
+        hex::encode_to_slice(self.salt(), &mut output[..len])?;
+        Ok((output, len))
     }
 }
 
+// Hashes message authentication code with a Keccak256 function
+// of the second-leftmost 16 bytes of the derived key together
+// with the full ciphertext
+fn compute_mac(mac_key: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let mut mac_hasher = Keccak256::new();
+    mac_hasher.update(mac_key);
+    mac_hasher.update(ciphertext);
+
+    let mut mac = [0; 32];
+    mac_hasher.finalize_into(mac.as_mut_slice().into());
+    mac
+}
+
+pub fn validate_cipher(cipher: &[u8]) -> Result<()> {
+    if cipher != CIPHER.as_bytes() {
+        let err = KsgenError::unsupported_cipher(
+            CIPHER.into(),
+            String::from_utf8_lossy(cipher).into(),
+        );
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+/// Builds the KDF by its keystore name; `n` is the full iterations count, as stored in keystores
+pub fn kdf_from_parts(kdf: &[u8], n: u32, r: u32, p: u32, c: u32, prf: &[u8]) -> Result<KdfInternal> {
+    match kdf {
+        // The same checks as for the caller-chosen parameters run before any KDF does
+        _ if kdf == SCRYPT.as_bytes() => ScryptBuilder::new().log_n(log_n(n)?).r(r).p(p).build(),
+        _ if kdf == PBKDF2.as_bytes() => {
+            if prf != PBKDF2_PRF.as_bytes() {
+                let err = KsgenError::unsupported_prf(
+                    PBKDF2_PRF.into(),
+                    String::from_utf8_lossy(prf).into(),
+                );
+                return Err(err);
+            }
+            if c == 0 || c > MAX_PBKDF2_C {
+                let err = KsgenError::invalid_kdf_param(
+                    "c".into(),
+                    format!("{} is out of the 1..={} range", c, MAX_PBKDF2_C),
+                );
+                return Err(err);
+            }
+
+            Ok(KdfInternal::Pbkdf2 { c })
+        },
+        _ => {
+            let err = KsgenError::unsupported_kdf(
+                format!("{} or {}", SCRYPT, PBKDF2),
+                String::from_utf8_lossy(kdf).into(),
+            );
+            Err(err)
+        },
+    }
+}
+
+// Keystore stores the full iterations count, scrypt accepts its log₂
+pub fn log_n(n: u32) -> Result<u8> {
+    if !n.is_power_of_two() {
+        let err = KsgenError::invalid_kdf_param(
+            "n".into(),
+            format!("{} is not a power of two", n),
+        );
+        return Err(err);
+    }
+
+    Ok(n.trailing_zeros() as u8)
+}
+
 fn to_hex<const N: usize>(input: &[u8]) -> Result<[u8; N]> {
     let mut output = [0; N];
     hex::encode_to_slice(input, &mut output)?;
     Ok(output)
 }
 
+pub fn from_hex<const N: usize>(input: &[u8], field_name: &str) -> Result<[u8; N]> {
+    let mut output = [0; N];
+    hex::decode_to_slice(input, &mut output)
+        .map_err(|e| KsgenError::decode_hex(field_name.into(), e))?;
+    Ok(output)
+}
+
+/// Decodes variable-length hex into the start of the output buffer
+pub fn from_hex_var<'b>(input: &[u8], output: &'b mut [u8], field_name: &str) -> Result<&'b [u8]> {
+    let len = input.len() / 2;
+    if len > output.len() {
+        let err = KsgenError::invalid_output(
+            output.len() * 2, input.len(),
+            field_name.into(),
+        );
+        return Err(err);
+    }
+
+    hex::decode_to_slice(input, &mut output[..len])
+        .map_err(|e| KsgenError::decode_hex(field_name.into(), e))?;
+    Ok(&output[..len])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,23 +544,24 @@ mod tests {
         let password = b"STRONG_PASSWORD";
         let secret_key = rand::random();
 
-        let keystore = KeystoreInternal::from_secret_key(secret_key, password).unwrap();
-        let ciphertext_hex = keystore.ciphertext_hex_encoded().unwrap();
+        let keystore = KeystoreInternal::from_secret_key(Zeroizing::new(secret_key), password, KdfInternal::scrypt()).unwrap();
+        let (ciphertext_hex, ciphertext_hex_len) = keystore.ciphertext_hex_encoded().unwrap();
         let iv_hex = keystore.cipherparams.iv_hex_encoded().unwrap();
 
         let kdfparams = keystore.kdfparams;
-        let scrypt_params = kdfparams.as_scrypt_params().unwrap();
+        let KdfInternal::Scrypt { n, r, p } = kdfparams.kdf else { unreachable!() };
+        let scrypt_params = Params::new(n, r, p, kdfparams.dklen).unwrap();
 
         let mut password_hash = [0u8; 16];
         scrypt::scrypt(
             password,
-            &kdfparams.salt,
+            kdfparams.salt(),
             &scrypt_params,
             &mut password_hash
         ).unwrap();
 
         let mut secret_key_output = [0; 32];
-        hex::decode_to_slice(ciphertext_hex, &mut secret_key_output).unwrap();
+        hex::decode_to_slice(&ciphertext_hex[..ciphertext_hex_len], &mut secret_key_output).unwrap();
 
         let mut iv = [0; 16];
         hex::decode_to_slice(iv_hex,&mut iv).unwrap();
@@ -176,4 +574,85 @@ mod tests {
 
         assert_eq!(secret_key, secret_key_output);
     }
+
+    #[test]
+    fn test_keystore_decrypt() {
+        let password = b"STRONG_PASSWORD";
+        let secret_key = rand::random();
+
+        let keystore = KeystoreInternal::from_secret_key(Zeroizing::new(secret_key), password, KdfInternal::scrypt()).unwrap();
+
+        assert_eq!(*keystore.decrypt_secret_key(password).unwrap(), secret_key);
+        assert!(matches!(keystore.decrypt(b"WRONG_PASSWORD"), Err(KsgenError::InvalidMacErr)));
+        assert!(keystore.verify_password(password).unwrap());
+        assert!(!keystore.verify_password(b"WRONG_PASSWORD").unwrap());
+    }
+
+    #[test]
+    fn test_keystore_reencrypt() {
+        let secret_key = rand::random();
+        let kdf = KdfInternal::Pbkdf2 { c: 1024 };
+
+        let keystore = KeystoreInternal::from_secret_key(Zeroizing::new(secret_key), b"OLD_PASSWORD", kdf).unwrap();
+        let reencrypted = keystore.reencrypt(b"OLD_PASSWORD", b"NEW_PASSWORD", None).unwrap();
+
+        assert_eq!(reencrypted.kdfparams.kdf, kdf);
+        assert_ne!(reencrypted.kdfparams.salt(), keystore.kdfparams.salt());
+        assert_eq!(*reencrypted.decrypt_secret_key(b"NEW_PASSWORD").unwrap(), secret_key);
+        assert!(matches!(reencrypted.decrypt(b"OLD_PASSWORD"), Err(KsgenError::InvalidMacErr)));
+        assert!(matches!(keystore.reencrypt(b"WRONG_PASSWORD", b"NEW_PASSWORD", None), Err(KsgenError::InvalidMacErr)));
+    }
+
+    #[test]
+    fn test_keystore_mnemonic() {
+        let password = b"STRONG_PASSWORD";
+        let phrase = b"legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let kdf = KdfInternal::Pbkdf2 { c: 1024 };
+
+        let keystore = KeystoreInternal::from_plaintext(PlaintextKind::Mnemonic, phrase, password, kdf).unwrap();
+        let (_, ciphertext_hex_len) = keystore.ciphertext_hex_encoded().unwrap();
+        assert_eq!(ciphertext_hex_len, phrase.len() * 2);
+
+        let reencrypted = keystore.reencrypt(password, b"NEW_PASSWORD", None).unwrap();
+        let plaintext = reencrypted.decrypt(b"NEW_PASSWORD").unwrap();
+        assert_eq!(plaintext.kind, PlaintextKind::Mnemonic);
+        assert_eq!(plaintext.as_slice(), phrase);
+
+        let err = keystore.decrypt_secret_key(password).err().unwrap();
+        assert!(matches!(err, KsgenError::UnexpectedPlaintextKindErr { .. }));
+
+        for (kind, len) in [(PlaintextKind::SecretKey, 31), (PlaintextKind::Entropy, 15), (PlaintextKind::Entropy, 18), (PlaintextKind::Mnemonic, 0)] {
+            let err = KeystoreInternal::from_plaintext(kind, &[0; 32][..len], password, kdf).err().unwrap();
+            assert!(matches!(err, KsgenError::InvalidPlaintextErr { .. }));
+        }
+    }
+
+    #[test]
+    fn test_scrypt_builder() {
+        assert_eq!(ScryptBuilder::standard().build().unwrap(), KdfInternal::Scrypt { n: 18, r: 8, p: 1 });
+        assert_eq!(ScryptBuilder::light().p(1).build().unwrap(), KdfInternal::Scrypt { n: 12, r: 8, p: 1 });
+
+        for builder in [ScryptBuilder::new().log_n(0), ScryptBuilder::new().r(0), ScryptBuilder::new().p(0)] {
+            assert!(matches!(builder.build(), Err(KsgenError::InvalidKdfParamErr { .. })));
+        }
+
+        let err = ScryptBuilder::new().log_n(16).r(1).build().err().unwrap();
+        assert!(matches!(err, KsgenError::InvalidKdfParamErr { field_name, .. } if field_name == "log_n"));
+
+        for builder in [ScryptBuilder::new().log_n(21), ScryptBuilder::new().log_n(20).r(9), ScryptBuilder::standard().p(1 << 7)] {
+            assert!(matches!(builder.build(), Err(KsgenError::InvalidKdfParamErr { .. })));
+        }
+    }
+
+    #[test]
+    fn test_keystore_pbkdf2() {
+        let password = b"STRONG_PASSWORD";
+        let secret_key = rand::random();
+
+        let keystore = KeystoreInternal::from_secret_key(Zeroizing::new(secret_key), password, KdfInternal::Pbkdf2 { c: 1024 }).unwrap();
+
+        assert_eq!(keystore.kdf, PBKDF2);
+        assert_eq!(*keystore.decrypt_secret_key(password).unwrap(), secret_key);
+        assert!(matches!(keystore.decrypt(b"WRONG_PASSWORD"), Err(KsgenError::InvalidMacErr)));
+    }
 }
\ No newline at end of file
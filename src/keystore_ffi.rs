@@ -1,13 +1,39 @@
 use std::{ffi::c_char, slice};
 
-use crate::{error::{KsgenError, Result}, keystore::{KdfParamsInternal, KeystoreInternal}};
+#[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+use crate::chains::bitcoin;
+
+#[cfg(feature = "litecoin")]
+use crate::chains::litecoin;
+
+#[cfg(feature = "ethereum")]
+use crate::chains::ethereum;
+
+#[cfg(feature = "sui")]
+use crate::chains::sui;
+
+use crate::{error::{KsgenError, Result}, keystore::{self, from_hex, from_hex_var, CipherParams as CipherParamsInternal, KdfInternal, KdfParamsInternal, KeystoreInternal, ScryptBuilder, MAX_PLAINTEXT_LEN, MAX_SALT_LEN}, mnemonic};
 
 pub const CIPHER_LEN: usize = 11 + 1;
 pub const IV_LEN: usize = 32 + 1;
 pub const CIPHERTEXT_LEN: usize = 64 + 1;
+pub const MNEMONIC_CIPHERTEXT_LEN: usize = 430 + 1;
 pub const KDF_LEN: usize = 6 + 1;
 pub const SALT_LEN: usize = 32 + 1;
+// Other wallets (geth, MetaMask) write 32-byte salts
+pub const IMPORTED_SALT_LEN: usize = 64 + 1;
+const _: () = assert!(IMPORTED_SALT_LEN == MAX_SALT_LEN * 2 + 1);
+pub const PRF_LEN: usize = 11 + 1;
 pub const MAC_LEN: usize = 64 + 1;
+pub const SECRET_KEY_LEN: usize = 32;
+pub const WIF_LEN: usize = 52 + 1;
+pub const MNEMONIC_LEN: usize = 215 + 1;
+pub const SEED_LEN: usize = 64;
+pub const DERIVATION_PATH_LEN: usize = 5;
+pub const SOLANA_KEYPAIR_JSON_LEN: usize = 257 + 1;
+pub const SOLANA_KEYPAIR_BASE58_LEN: usize = 88 + 1;
+pub const SUI_PRIVATE_KEY_BECH32_LEN: usize = 70 + 1;
+pub const SUI_PRIVATE_KEY_BASE64_LEN: usize = 44 + 1;
 
 #[repr(C)]
 pub struct Keystore {
@@ -17,35 +43,76 @@ pub struct Keystore {
     kdf: SizedStr,
     kdfparams: KdfParams,
     mac: SizedStr,
+    /// A `PlaintextKind` value, nullable for keystores of secret keys only
+    plaintext_kind: *mut u32,
 }
 
 impl Keystore {
     pub unsafe fn write(&self, keystore: KeystoreInternal) -> Result<()> {
         let cipherparams = keystore.cipherparams.iv_hex_encoded()?;
-        let ciphertext = keystore.ciphertext_hex_encoded()?;
+        let (ciphertext, ciphertext_len) = keystore.ciphertext_hex_encoded()?;
         let mac = keystore.mac_hex_encoded()?;
 
+        // Fails before anything is written
+        self.ciphertext.validate_capacity(ciphertext_len + 1, "ciphertext")?;
+        self.kdfparams.salt.validate_capacity(keystore.kdfparams.salt().len() * 2 + 1, "salt")?;
+        if self.plaintext_kind.is_null() && keystore.plaintext_kind != PlaintextKind::SecretKey {
+            return Err(KsgenError::null_ptr("plaintext_kind".into()));
+        }
+
         self.kdfparams.write_kdf_params_internal(&keystore.kdfparams)?;
 
         self.cipher.write_bytes(keystore.cipher.as_bytes());
         self.cipherparams.iv.write_bytes(&cipherparams);
-        self.ciphertext.write_bytes(&ciphertext);
+        self.ciphertext.write_bytes(&ciphertext[..ciphertext_len]);
         self.kdf.write_bytes(keystore.kdf.as_bytes());
         self.mac.write_bytes(&mac);
+        if !self.plaintext_kind.is_null() {
+            *self.plaintext_kind = keystore.plaintext_kind as u32;
+        }
 
         Ok(())
     }
 
+    pub unsafe fn read(&self) -> Result<KeystoreInternal<'static>> {
+        keystore::validate_cipher(self.cipher.as_slice())?;
+
+        let iv = from_hex(self.cipherparams.iv.as_slice(), "iv")?;
+        let mut ciphertext = [0; MAX_PLAINTEXT_LEN];
+        let ciphertext = from_hex_var(self.ciphertext.as_slice(), &mut ciphertext, "ciphertext")?;
+        let kdfparams = self.kdfparams.read_kdf_params_internal(self.kdf.as_slice())?;
+        let mac = from_hex(self.mac.as_slice(), "mac")?;
+
+        let plaintext_kind = match self.plaintext_kind.as_ref() {
+            Some(plaintext_kind) => PlaintextKind::try_from(*plaintext_kind)?,
+            None => PlaintextKind::SecretKey,
+        };
+
+        KeystoreInternal::new(
+            CipherParamsInternal::from_iv(iv),
+            plaintext_kind,
+            ciphertext, kdfparams, mac,
+        )
+    }
+
     pub fn validate(&self) -> Result<()> {
         self.cipher.validate(CIPHER_LEN, "cipher")?;
         self.cipherparams.iv.validate(IV_LEN, "iv")?;
-        self.ciphertext.validate(CIPHERTEXT_LEN, "ciphertext")?;
+        self.ciphertext.validate_capacity(CIPHERTEXT_LEN, "ciphertext")?;
         self.kdf.validate(KDF_LEN, "kdf")?;
         self.kdfparams.validate()?;
         self.mac.validate(MAC_LEN, "mac")?;
 
         Ok(())
     }
+
+    /// Keystores of a whole phrase need the kind pointer and the larger ciphertext buffer
+    pub fn validate_mnemonic(&self) -> Result<()> {
+        self.validate()?;
+        self.ciphertext.validate_capacity(MNEMONIC_CIPHERTEXT_LEN, "ciphertext")?;
+
+        ptr_is_null(self.plaintext_kind, "plaintext_kind")
+    }
 }
 
 #[repr(C)]
@@ -53,6 +120,7 @@ pub struct CipherParams {
     iv: SizedStr,
 }
 
+/// Only the parameter set of the filled `kdf` is meaningful, the other one is zeroed
 #[repr(C)]
 pub struct KdfParams {
     dklen: *mut usize,
@@ -60,21 +128,56 @@ pub struct KdfParams {
     r: *mut u32,
     p: *mut u32,
     salt: SizedStr,
+    kdf: *mut KdfKind,
+    c: *mut u32,
+    prf: SizedStr,
 }
 
 impl KdfParams {
     unsafe fn write_kdf_params_internal(&self, params: &KdfParamsInternal) -> Result<()> {
-        let salt = params.salt_hex_encoded()?;
+        let (salt, salt_len) = params.salt_hex_encoded()?;
 
         *self.dklen = params.dklen;
-        *self.n = 2u32.pow(params.n.into());
-        *self.r = params.r;
-        *self.p = params.p;
-        self.salt.write_bytes(&salt);
+        self.salt.write_bytes(&salt[..salt_len]);
+
+        match params.kdf {
+            KdfInternal::Scrypt { n, r, p } => {
+                *self.kdf = KdfKind::Scrypt;
+                *self.n = 2u32.pow(n.into());
+                *self.r = r;
+                *self.p = p;
+                *self.c = 0;
+                self.prf.write_bytes(&[]);
+            },
+            KdfInternal::Pbkdf2 { c } => {
+                *self.kdf = KdfKind::Pbkdf2;
+                *self.n = 0;
+                *self.r = 0;
+                *self.p = 0;
+                *self.c = c;
+                self.prf.write_bytes(keystore::PBKDF2_PRF.as_bytes());
+            },
+        }
 
         Ok(())
     }
 
+    unsafe fn read_kdf_params_internal(&self, kdf: &[u8]) -> Result<KdfParamsInternal> {
+        let mut salt = [0; MAX_SALT_LEN];
+        let salt = from_hex_var(self.salt.as_slice(), &mut salt, "salt")?;
+
+        let kdf = keystore::kdf_from_parts(
+            kdf,
+            *self.n,
+            *self.r,
+            *self.p,
+            *self.c,
+            self.prf.as_slice(),
+        )?;
+
+        KdfParamsInternal::from_parts(*self.dklen, kdf, salt)
+    }
+
     #[inline]
     fn validate(&self) -> Result<()> {
         ptr_is_null(self.dklen, "dklen")?;
@@ -82,12 +185,154 @@ impl KdfParams {
         ptr_is_null(self.r, "r")?;
         ptr_is_null(self.p, "p")?;
 
-        self.salt.validate(SALT_LEN, "salt")?;
+        self.salt.validate_capacity(SALT_LEN, "salt")?;
+
+        ptr_is_null(self.kdf, "kdf")?;
+        ptr_is_null(self.c, "c")?;
+
+        self.prf.validate(PRF_LEN, "prf")?;
 
         Ok(())
     }
 }
 
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum KdfKind {
+    Scrypt = 0,
+    Pbkdf2 = 1,
+}
+
+impl TryFrom<u32> for KdfKind {
+    type Error = KsgenError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Scrypt),
+            1 => Ok(Self::Pbkdf2),
+            _ => Err(KsgenError::invalid_enum_value("kdf".to_string(), value)),
+        }
+    }
+}
+
+/// What the ciphertext holds, so decryption knows how to interpret it. Crosses the FFI as a `u32`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PlaintextKind {
+    /// The 32-byte secret key of geth-compatible keystores
+    #[default]
+    SecretKey = 0,
+    /// 16 to 32 bytes of BIP39 entropy
+    Entropy = 1,
+    /// A BIP39 English phrase
+    Mnemonic = 2,
+}
+
+impl TryFrom<u32> for PlaintextKind {
+    type Error = KsgenError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::SecretKey),
+            1 => Ok(Self::Entropy),
+            2 => Ok(Self::Mnemonic),
+            _ => Err(KsgenError::invalid_plaintext(format!("unknown kind ({})", value))),
+        }
+    }
+}
+
+impl PlaintextKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SecretKey => "secret-key",
+            Self::Entropy => "entropy",
+            Self::Mnemonic => "mnemonic",
+        }
+    }
+
+    pub fn from_name(name: &[u8]) -> Result<Self> {
+        match name {
+            b"secret-key" => Ok(Self::SecretKey),
+            b"entropy" => Ok(Self::Entropy),
+            b"mnemonic" => Ok(Self::Mnemonic),
+            _ => Err(KsgenError::invalid_plaintext(format!("unknown kind ({})", String::from_utf8_lossy(name)))),
+        }
+    }
+
+    pub fn validate_len(self, len: usize) -> Result<()> {
+        let valid = match self {
+            Self::SecretKey => len == SECRET_KEY_LEN,
+            Self::Entropy => (16..=mnemonic::MAX_ENTROPY_LEN).contains(&len) && len.is_multiple_of(4),
+            Self::Mnemonic => (1..=mnemonic::MAX_PHRASE_LEN).contains(&len),
+        };
+
+        if !valid {
+            return Err(KsgenError::invalid_plaintext(format!("{} bytes of {:?}", len, self)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Crosses the FFI as a `u32`
+#[derive(Debug, Clone, Copy)]
+pub enum ScryptPreset {
+    Default = 0,
+    Light = 1,
+    Standard = 2,
+    /// Uses the explicit `log_n`, `r` and `p` options
+    Custom = 3,
+}
+
+impl TryFrom<u32> for ScryptPreset {
+    type Error = KsgenError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Default),
+            1 => Ok(Self::Light),
+            2 => Ok(Self::Standard),
+            3 => Ok(Self::Custom),
+            _ => Err(KsgenError::invalid_enum_value("scrypt_preset".to_string(), value)),
+        }
+    }
+}
+
+/// Passed as a nullable pointer, the enums are read as `u32` and checked
+#[repr(C)]
+pub struct KdfOptions {
+    kdf: u32,
+    scrypt_preset: u32,
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl KdfOptions {
+    /// Returns `None` for a null pointer, so the caller picks its own fallback
+    pub unsafe fn read_kdf_internal(options: *const Self) -> Result<Option<KdfInternal>> {
+        let Some(options) = options.as_ref() else {
+            return Ok(None);
+        };
+
+        match KdfKind::try_from(options.kdf)? {
+            KdfKind::Scrypt => {
+                let builder = match ScryptPreset::try_from(options.scrypt_preset)? {
+                    ScryptPreset::Default => ScryptBuilder::new(),
+                    ScryptPreset::Light => ScryptBuilder::light(),
+                    ScryptPreset::Standard => ScryptBuilder::standard(),
+                    ScryptPreset::Custom => ScryptBuilder::new()
+                        .log_n(options.log_n)
+                        .r(options.r)
+                        .p(options.p),
+                };
+
+                builder.build().map(Some)
+            },
+            KdfKind::Pbkdf2 => Ok(Some(KdfInternal::pbkdf2())),
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub enum Network {
@@ -100,6 +345,299 @@ pub enum Network {
     Tron,
 }
 
+/// Crosses the FFI as a `u32`
+#[derive(Debug, Clone, Copy, Default)]
+pub enum AddressKind {
+    /// The recommended address kind of the network
+    #[default]
+    Default = 0,
+    P2pkh = 1,
+    P2pkhUncompressed = 2,
+    P2shP2wpkh = 3,
+    P2wpkh = 4,
+    P2tr = 5,
+    Eip55 = 6,
+    /// Uses the `chain_id` option
+    Eip1191 = 7,
+    /// Sui secp256k1 (flag 0x01) account
+    Secp256k1 = 8,
+    /// Sui secp256r1 (flag 0x02) account, requires the `sui-secp256r1` feature
+    Secp256r1 = 9,
+}
+
+impl TryFrom<u32> for AddressKind {
+    type Error = KsgenError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Default),
+            1 => Ok(Self::P2pkh),
+            2 => Ok(Self::P2pkhUncompressed),
+            3 => Ok(Self::P2shP2wpkh),
+            4 => Ok(Self::P2wpkh),
+            5 => Ok(Self::P2tr),
+            6 => Ok(Self::Eip55),
+            7 => Ok(Self::Eip1191),
+            8 => Ok(Self::Secp256k1),
+            9 => Ok(Self::Secp256r1),
+            _ => Err(KsgenError::invalid_enum_value("kind".to_string(), value)),
+        }
+    }
+}
+
+impl AddressKind {
+    pub fn validate(self, network: &Network) -> Result<()> {
+        let supported = match network {
+            Network::Bitcoin => matches!(
+                self,
+                Self::Default
+                    | Self::P2pkh
+                    | Self::P2pkhUncompressed
+                    | Self::P2shP2wpkh
+                    | Self::P2wpkh
+                    | Self::P2tr,
+            ),
+            Network::Litecoin => matches!(
+                self,
+                Self::Default
+                    | Self::P2pkh
+                    | Self::P2pkhUncompressed
+                    | Self::P2shP2wpkh
+                    | Self::P2wpkh,
+            ),
+            Network::Ethereum => matches!(self, Self::Default | Self::Eip55 | Self::Eip1191),
+            Network::Sui => {
+                matches!(self, Self::Default | Self::Secp256k1)
+                    || (matches!(self, Self::Secp256r1) && cfg!(feature = "sui-secp256r1"))
+            },
+            _ => matches!(self, Self::Default),
+        };
+
+        if !supported {
+            let err = KsgenError::unsupported_address_kind(
+                format!("{:?}", network),
+                format!("{:?}", self),
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+    pub fn bitcoin_address_type(self) -> bitcoin::AddressType {
+        match self {
+            Self::P2pkh => bitcoin::AddressType::P2pkh,
+            Self::P2pkhUncompressed => bitcoin::AddressType::P2pkhUncompressed,
+            Self::P2shP2wpkh => bitcoin::AddressType::P2shP2wpkh,
+            Self::P2wpkh => bitcoin::AddressType::P2wpkh,
+            Self::P2tr => bitcoin::AddressType::P2tr,
+            _ => bitcoin::AddressType::default(),
+        }
+    }
+
+    #[cfg(feature = "sui")]
+    pub fn sui_signature_scheme(self) -> sui::SignatureScheme {
+        match self {
+            Self::Secp256k1 => sui::SignatureScheme::Secp256k1,
+            #[cfg(feature = "sui-secp256r1")]
+            Self::Secp256r1 => sui::SignatureScheme::Secp256r1,
+            _ => sui::SignatureScheme::default(),
+        }
+    }
+
+    #[cfg(feature = "sui")]
+    pub fn from_sui_signature_scheme(scheme: sui::SignatureScheme) -> Self {
+        match scheme {
+            sui::SignatureScheme::Ed25519 => Self::Default,
+            sui::SignatureScheme::Secp256k1 => Self::Secp256k1,
+            #[cfg(feature = "sui-secp256r1")]
+            sui::SignatureScheme::Secp256r1 => Self::Secp256r1,
+        }
+    }
+}
+
+/// Crosses the FFI as a `u32`
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Chain {
+    #[default]
+    Mainnet = 0,
+    Testnet = 1,
+    Signet = 2,
+    Regtest = 3,
+}
+
+impl TryFrom<u32> for Chain {
+    type Error = KsgenError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Mainnet),
+            1 => Ok(Self::Testnet),
+            2 => Ok(Self::Signet),
+            3 => Ok(Self::Regtest),
+            _ => Err(KsgenError::invalid_enum_value("chain".to_string(), value)),
+        }
+    }
+}
+
+impl Chain {
+    pub fn validate(self, network: &Network) -> Result<()> {
+        let supported = match network {
+            Network::Bitcoin | Network::Litecoin => true,
+            _ => matches!(self, Self::Mainnet),
+        };
+
+        if !supported {
+            let err = KsgenError::unsupported_chain(
+                format!("{:?}", network),
+                format!("{:?}", self),
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+    pub fn bitcoin_chain(self) -> bitcoin::Chain {
+        match self {
+            Self::Mainnet => bitcoin::Chain::Mainnet,
+            Self::Testnet => bitcoin::Chain::Testnet,
+            Self::Signet => bitcoin::Chain::Signet,
+            Self::Regtest => bitcoin::Chain::Regtest,
+        }
+    }
+}
+
+/// Passed as a nullable pointer, the enums are read as `u32` and checked
+#[repr(C)]
+pub struct AddressOptions {
+    kind: u32,
+    chain: u32,
+    /// EIP-1191 chain id, ignored by other address kinds
+    chain_id: u64,
+}
+
+impl AddressOptions {
+    /// Returns the default kind on the mainnet for a null pointer
+    pub unsafe fn read(options: *const Self) -> Result<AddressOptionsInternal> {
+        let Some(options) = options.as_ref() else {
+            return Ok(AddressOptionsInternal::default());
+        };
+
+        Ok(AddressOptionsInternal {
+            kind: AddressKind::try_from(options.kind)?,
+            chain: Chain::try_from(options.chain)?,
+            chain_id: options.chain_id,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AddressOptionsInternal {
+    pub kind: AddressKind,
+    pub chain: Chain,
+    pub chain_id: u64,
+}
+
+impl AddressOptionsInternal {
+    pub fn validate(&self, network: &Network) -> Result<()> {
+        self.kind.validate(network)?;
+        self.chain.validate(network)?;
+
+        if matches!(self.kind, AddressKind::Eip1191) && self.chain_id == 0 {
+            return Err(KsgenError::invalid_chain_id(format!("{:?}", network), self.chain_id));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+    pub fn bitcoin_network_params(&self, network: &Network) -> Result<&'static bitcoin::NetworkParams> {
+        let chain = self.chain.bitcoin_chain();
+
+        match network {
+            #[cfg(feature = "bitcoin")]
+            Network::Bitcoin => Ok(bitcoin::network_params(chain)),
+            #[cfg(feature = "litecoin")]
+            Network::Litecoin => litecoin::network_params(chain),
+            _ => Err(KsgenError::unsupported_chain(format!("{:?}", network), format!("{:?}", self.chain))),
+        }
+    }
+
+    #[cfg(feature = "ethereum")]
+    pub fn ethereum_address_format(&self) -> ethereum::AddressFormat {
+        match self.kind {
+            AddressKind::Eip55 => ethereum::AddressFormat::Eip55,
+            AddressKind::Eip1191 => ethereum::AddressFormat::Eip1191 { chain_id: self.chain_id },
+            _ => ethereum::AddressFormat::default(),
+        }
+    }
+}
+
+/// Crosses the FFI as a `u32`
+#[derive(Debug, Clone, Copy)]
+pub enum SolanaKeypairFormat {
+    /// The solana-keygen `id.json` byte array
+    Json = 0,
+    /// The Base58 string accepted by Phantom and other browser wallets
+    Base58 = 1,
+}
+
+impl TryFrom<u32> for SolanaKeypairFormat {
+    type Error = KsgenError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Json),
+            1 => Ok(Self::Base58),
+            _ => Err(KsgenError::invalid_enum_value("format".to_string(), value)),
+        }
+    }
+}
+
+impl SolanaKeypairFormat {
+    #[inline]
+    pub fn buffer_len(self) -> usize {
+        match self {
+            Self::Json => SOLANA_KEYPAIR_JSON_LEN,
+            Self::Base58 => SOLANA_KEYPAIR_BASE58_LEN,
+        }
+    }
+}
+
+/// Crosses the FFI as a `u32`
+#[derive(Debug, Clone, Copy)]
+pub enum SuiPrivateKeyFormat {
+    /// The `suiprivkey1...` string of the Sui CLI and wallets
+    Bech32 = 0,
+    /// An entry of the `sui.keystore` file
+    Base64 = 1,
+}
+
+impl TryFrom<u32> for SuiPrivateKeyFormat {
+    type Error = KsgenError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Bech32),
+            1 => Ok(Self::Base64),
+            _ => Err(KsgenError::invalid_enum_value("format".to_string(), value)),
+        }
+    }
+}
+
+impl SuiPrivateKeyFormat {
+    #[inline]
+    pub fn buffer_len(self) -> usize {
+        match self {
+            Self::Bech32 => SUI_PRIVATE_KEY_BECH32_LEN,
+            Self::Base64 => SUI_PRIVATE_KEY_BASE64_LEN,
+        }
+    }
+}
+
 #[repr(C)]
 pub struct SizedStr {
     str: *mut c_char,
@@ -107,9 +645,37 @@ pub struct SizedStr {
 }
 
 impl SizedStr {
+    /// Writes the input followed by the NUL terminator; the input may be shorter than the buffer
     pub unsafe fn write_bytes(&self, input: &[u8]) {
         let output = slice::from_raw_parts_mut(self.str as *mut u8, self.len);
-        output[..self.len - 1].copy_from_slice(input);
+        output[..input.len()].copy_from_slice(input);
+        output[input.len()..].fill(0);
+    }
+
+    /// Returns the written bytes up to the first NUL terminator
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        let input = slice::from_raw_parts(self.str as *const u8, self.len);
+        input.iter()
+            .position(|x| x == &0)
+            .map(|i| &input[..i])
+            .unwrap_or(input)
+    }
+
+    /// Returns the whole buffer, leaving the last byte for the NUL terminator
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        let output = slice::from_raw_parts_mut(self.str as *mut u8, self.len);
+        output[self.len - 1] = 0;
+        &mut output[..self.len - 1]
+    }
+
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.str.is_null()
+    }
+
+    #[inline]
+    pub fn validate_ptr(&self, field_name: &str) -> Result<()> {
+        ptr_is_null(self.str, field_name)
     }
 
     pub fn validate(&self, expected_len: usize, field_name: &str) -> Result<()> {
@@ -125,6 +691,21 @@ impl SizedStr {
 
         Ok(())
     }
+
+    /// For outputs of a variable len, the buffer must hold at least `min_len` bytes
+    pub fn validate_capacity(&self, min_len: usize, field_name: &str) -> Result<()> {
+        ptr_is_null(self.str, field_name)?;
+
+        if self.len < min_len {
+            let err = KsgenError::invalid_output(
+                min_len, self.len,
+                field_name.into(),
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
 }
 
 #[repr(C)]
@@ -144,7 +725,134 @@ impl SizedStrConst {
     }
 }
 
-trait IsNull {
+#[repr(C)]
+pub struct SizedBytes {
+    bytes: *mut u8,
+    len: usize,
+}
+
+impl SizedBytes {
+    pub unsafe fn write_bytes(&self, input: &[u8]) {
+        let output = slice::from_raw_parts_mut(self.bytes, self.len);
+        output.copy_from_slice(input);
+    }
+
+    pub fn validate(&self, expected_len: usize, field_name: &str) -> Result<()> {
+        ptr_is_null(self.bytes, field_name)?;
+
+        if self.len != expected_len {
+            let err = KsgenError::invalid_output(
+                expected_len, self.len,
+                field_name.into(),
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
+}
+
+#[repr(C)]
+pub struct SizedBytesConst {
+    bytes: *const u8,
+    len: usize,
+}
+
+impl SizedBytesConst {
+    pub fn validate(&self, expected_len: usize, field_name: &str) -> Result<()> {
+        ptr_is_null(self.bytes, field_name)?;
+
+        if self.len != expected_len {
+            let err = KsgenError::invalid_output(
+                expected_len, self.len,
+                field_name.into(),
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        slice::from_raw_parts(self.bytes, self.len)
+    }
+}
+
+/// Child indexes from the master key, hardened ones have the `HARDENED` bit set
+#[repr(C)]
+pub struct DerivationPath {
+    indexes: *const u32,
+    len: usize,
+}
+
+impl DerivationPath {
+    pub fn validate(&self, field_name: &str) -> Result<()> {
+        // The master key itself is an empty path
+        if self.len > 0 {
+            ptr_is_null(self.indexes, field_name)?;
+        }
+
+        Ok(())
+    }
+
+    pub unsafe fn as_slice(&self) -> &[u32] {
+        if self.len == 0 {
+            return &[];
+        }
+
+        slice::from_raw_parts(self.indexes, self.len)
+    }
+}
+
+#[repr(C)]
+pub struct DerivationPathMut {
+    indexes: *mut u32,
+    len: usize,
+}
+
+impl DerivationPathMut {
+    pub fn validate(&self, expected_len: usize, field_name: &str) -> Result<()> {
+        ptr_is_null(self.indexes, field_name)?;
+
+        if self.len != expected_len {
+            let err = KsgenError::invalid_output(
+                expected_len, self.len,
+                field_name.into(),
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// For outputs of a variable len, the buffer must hold at least `min_len` indexes
+    pub fn validate_capacity(&self, min_len: usize, field_name: &str) -> Result<()> {
+        ptr_is_null(self.indexes, field_name)?;
+
+        if self.len < min_len {
+            let err = KsgenError::invalid_output(
+                min_len, self.len,
+                field_name.into(),
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the indexes and zeroes the rest of the buffer, returns the written len
+    pub unsafe fn write_indexes(&self, input: &[u32]) -> usize {
+        let output = slice::from_raw_parts_mut(self.indexes, self.len);
+        output[..input.len()].copy_from_slice(input);
+        output[input.len()..].fill(0);
+
+        input.len()
+    }
+}
+
+pub(crate) trait IsNull {
+    // Raw pointers are Copy, taking them by value mirrors the inherent method
+    #[allow(clippy::wrong_self_convention)]
     fn is_null(self) -> bool;
 }
 
@@ -163,7 +871,7 @@ impl<T> IsNull for *mut T {
 }
 
 #[inline]
-fn ptr_is_null<T: IsNull>(ptr: T, field_name: &str) -> Result<()> {
+pub(crate) fn ptr_is_null<T: IsNull>(ptr: T, field_name: &str) -> Result<()> {
     if ptr.is_null() {
         return Err(KsgenError::null_ptr(field_name.into()));
     }
@@ -171,3 +879,59 @@ fn ptr_is_null<T: IsNull>(ptr: T, field_name: &str) -> Result<()> {
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_kdf_internal() {
+        let read = |kdf, scrypt_preset, log_n, r, p| unsafe {
+            KdfOptions::read_kdf_internal(&KdfOptions { kdf, scrypt_preset, log_n, r, p })
+        };
+
+        assert_eq!(read(KdfKind::Scrypt as u32, ScryptPreset::Default as u32, 0, 0, 0).unwrap(), Some(KdfInternal::Scrypt { n: 13, r: 8, p: 8 }));
+        assert_eq!(read(KdfKind::Scrypt as u32, ScryptPreset::Light as u32, 0, 0, 0).unwrap(), Some(KdfInternal::Scrypt { n: 12, r: 8, p: 6 }));
+        assert_eq!(read(KdfKind::Scrypt as u32, ScryptPreset::Standard as u32, 0, 0, 0).unwrap(), Some(KdfInternal::Scrypt { n: 18, r: 8, p: 1 }));
+        assert_eq!(read(KdfKind::Scrypt as u32, ScryptPreset::Custom as u32, 14, 8, 2).unwrap(), Some(KdfInternal::Scrypt { n: 14, r: 8, p: 2 }));
+        assert_eq!(read(KdfKind::Pbkdf2 as u32, ScryptPreset::Standard as u32, 0, 0, 0).unwrap(), Some(KdfInternal::pbkdf2()));
+
+        // Presets ignore the explicit options, Custom checks them
+        assert!(read(KdfKind::Scrypt as u32, ScryptPreset::Light as u32, 30, 0, 0).is_ok());
+        assert!(matches!(
+            read(KdfKind::Scrypt as u32, ScryptPreset::Custom as u32, 30, 8, 1),
+            Err(KsgenError::InvalidKdfParamErr { .. }),
+        ));
+
+        assert!(matches!(read(2, 0, 0, 0, 0), Err(KsgenError::InvalidEnumValueErr { value: 2, .. })));
+        assert!(matches!(read(KdfKind::Scrypt as u32, 4, 0, 0, 0), Err(KsgenError::InvalidEnumValueErr { value: 4, .. })));
+
+        assert_eq!(unsafe { KdfOptions::read_kdf_internal(std::ptr::null()) }.unwrap(), None);
+    }
+
+    #[test]
+    fn test_address_options_chain_id() {
+        let options = AddressOptionsInternal { kind: AddressKind::Eip1191, chain: Chain::Mainnet, chain_id: 0 };
+        assert!(matches!(
+            options.validate(&Network::Ethereum),
+            Err(KsgenError::InvalidChainIdErr { chain_id: 0, .. }),
+        ));
+
+        let options = AddressOptionsInternal { chain_id: 30, ..options };
+        assert!(options.validate(&Network::Ethereum).is_ok());
+    }
+
+    #[test]
+    fn test_read_address_options() {
+        let read = |kind, chain| unsafe { AddressOptions::read(&AddressOptions { kind, chain, chain_id: 30 }) };
+
+        let options = read(AddressKind::Eip1191 as u32, Chain::Mainnet as u32).unwrap();
+        assert!(matches!(options.kind, AddressKind::Eip1191));
+        assert_eq!(options.chain_id, 30);
+
+        assert!(matches!(read(10, 0), Err(KsgenError::InvalidEnumValueErr { value: 10, .. })));
+        assert!(matches!(read(0, 4), Err(KsgenError::InvalidEnumValueErr { value: 4, .. })));
+
+        let options = unsafe { AddressOptions::read(std::ptr::null()) }.unwrap();
+        assert!(matches!((options.kind, options.chain), (AddressKind::Default, Chain::Mainnet)));
+    }
+}
\ No newline at end of file
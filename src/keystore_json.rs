@@ -0,0 +1,383 @@
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{KsgenError, Result},
+    keystore::{self, from_hex, from_hex_var, CipherParams, KdfInternal, KdfParamsInternal, KeystoreInternal, MAX_PLAINTEXT_LEN, MAX_SALT_LEN},
+    keystore_ffi::{Network, PlaintextKind},
+};
+
+const VERSION: u8 = 3;
+// Only Ethereum addresses are copied to be lowercased, 0x and 40 hex chars
+const MAX_ADDRESS_LEN: usize = 42;
+
+// Fields are declared in the order geth writes them
+#[derive(Deserialize, Serialize)]
+struct KeystoreV3<'a> {
+    // Only written, the address of an imported document isn't trusted
+    #[serde(skip_deserializing)]
+    address: &'a str,
+    // geth writes "Crypto" in older keystores
+    #[serde(borrow, alias = "Crypto")]
+    crypto: CryptoV3<'a>,
+    #[serde(skip_deserializing)]
+    id: &'a str,
+    version: u8,
+    // Secret key documents omit the field, so they stay identical to the ones of geth
+    #[serde(borrow, rename = "x-ksgen", skip_serializing_if = "Option::is_none")]
+    extension: Option<ExtensionV3<'a>>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ExtensionV3<'a> {
+    plaintext: &'a str,
+}
+
+#[derive(Deserialize, Serialize)]
+struct CryptoV3<'a> {
+    cipher: &'a str,
+    ciphertext: &'a str,
+    #[serde(borrow)]
+    cipherparams: CipherParamsV3<'a>,
+    kdf: &'a str,
+    #[serde(borrow)]
+    kdfparams: KdfParamsV3<'a>,
+    mac: &'a str,
+}
+
+#[derive(Deserialize, Serialize)]
+struct CipherParamsV3<'a> {
+    iv: &'a str,
+}
+
+// Holds both scrypt (n, r, p) and pbkdf2 (c, prf) parameter sets, the unused
+// one is left out of the output
+#[derive(Deserialize, Serialize)]
+struct KdfParamsV3<'a> {
+    #[serde(default, skip_serializing_if = "is_zero")]
+    c: u32,
+    dklen: usize,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    n: u32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    p: u32,
+    #[serde(default, skip_serializing_if = "str::is_empty")]
+    prf: &'a str,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    r: u32,
+    salt: &'a str,
+}
+
+/// Parses a Web3 Secret Storage v3 document produced by geth, MetaMask, ethers.js etc.
+pub fn parse_v3(input: &[u8]) -> Result<KeystoreInternal<'static>> {
+    let keystore: KeystoreV3 = serde_json::from_slice(input)?;
+    if keystore.version != VERSION {
+        return Err(KsgenError::unsupported_version(VERSION, keystore.version));
+    }
+
+    let crypto = keystore.crypto;
+    keystore::validate_cipher(crypto.cipher.as_bytes())?;
+
+    let plaintext_kind = match keystore.extension {
+        Some(extension) => PlaintextKind::from_name(extension.plaintext.as_bytes())?,
+        None => PlaintextKind::SecretKey,
+    };
+
+    let iv = from_hex(crypto.cipherparams.iv.as_bytes(), "iv")?;
+    let mut ciphertext = [0; MAX_PLAINTEXT_LEN];
+    let ciphertext = from_hex_var(crypto.ciphertext.as_bytes(), &mut ciphertext, "ciphertext")?;
+    let mac = from_hex(crypto.mac.as_bytes(), "mac")?;
+
+    let kdfparams = crypto.kdfparams;
+    let mut salt = [0; MAX_SALT_LEN];
+    let salt = from_hex_var(kdfparams.salt.as_bytes(), &mut salt, "salt")?;
+
+    let kdf = keystore::kdf_from_parts(
+        crypto.kdf.as_bytes(),
+        kdfparams.n,
+        kdfparams.r,
+        kdfparams.p,
+        kdfparams.c,
+        kdfparams.prf.as_bytes(),
+    )?;
+
+    KeystoreInternal::new(
+        CipherParams::from_iv(iv),
+        plaintext_kind,
+        ciphertext,
+        KdfParamsInternal::from_parts(kdfparams.dklen, kdf, salt)?,
+        mac,
+    )
+}
+
+/// Renders a Web3 Secret Storage v3 document compatible with geth
+pub fn write_v3<W: Write>(
+    output: &mut W,
+    keystore: &KeystoreInternal,
+    address: &[u8],
+    network: &Network,
+) -> Result<()> {
+    let kdfparams = &keystore.kdfparams;
+
+    let mut address_lowercase = [0; MAX_ADDRESS_LEN];
+    let address = normalize_address(address, network, &mut address_lowercase)?;
+
+    let (ciphertext, ciphertext_len) = keystore.ciphertext_hex_encoded()?;
+    let iv = keystore.cipherparams.iv_hex_encoded()?;
+    let (salt, salt_len) = kdfparams.salt_hex_encoded()?;
+    let mac = keystore.mac_hex_encoded()?;
+    let id = uuid_v4();
+
+    let (n, r, p, c, prf) = match kdfparams.kdf {
+        KdfInternal::Scrypt { n, r, p } => (2u32.pow(n.into()), r, p, 0, ""),
+        KdfInternal::Pbkdf2 { c } => (0, 0, 0, c, keystore::PBKDF2_PRF),
+    };
+
+    let document = KeystoreV3 {
+        address,
+        crypto: CryptoV3 {
+            cipher: keystore.cipher,
+            ciphertext: ascii_str(&ciphertext[..ciphertext_len]),
+            cipherparams: CipherParamsV3 { iv: ascii_str(&iv) },
+            kdf: keystore.kdf,
+            kdfparams: KdfParamsV3 {
+                c,
+                dklen: kdfparams.dklen,
+                n,
+                p,
+                prf,
+                r,
+                salt: ascii_str(&salt[..salt_len]),
+            },
+            mac: ascii_str(&mac),
+        },
+        id: ascii_str(&id),
+        version: VERSION,
+        extension: match keystore.plaintext_kind {
+            PlaintextKind::SecretKey => None,
+            plaintext_kind => Some(ExtensionV3 { plaintext: plaintext_kind.name() }),
+        },
+    };
+    serde_json::to_writer(output, &document)?;
+
+    Ok(())
+}
+
+/// Returns the rendered document len without writing it anywhere
+pub fn v3_len(keystore: &KeystoreInternal, address: &[u8], network: &Network) -> Result<usize> {
+    let mut counter = Counter(0);
+    write_v3(&mut counter, keystore, address, network)?;
+    Ok(counter.0)
+}
+
+// Every supported encoding (hex, Base58, bech32) is alphanumeric. geth stores
+// Ethereum addresses as lowercase hex without the 0x prefix
+fn normalize_address<'a>(address: &'a [u8], network: &Network, buf: &'a mut [u8; MAX_ADDRESS_LEN]) -> Result<&'a str> {
+    let address = match network {
+        Network::Ethereum => {
+            let address = address.strip_prefix(b"0x").unwrap_or(address);
+            let buf = buf
+                .get_mut(..address.len())
+                .ok_or_else(|| KsgenError::invalid_address(format!("too long ({} bytes)", address.len())))?;
+            buf.copy_from_slice(address);
+            buf.make_ascii_lowercase();
+            &*buf
+        },
+        _ => address,
+    };
+
+    if !address.iter().all(u8::is_ascii_alphanumeric) {
+        return Err(KsgenError::invalid_address("only ASCII letters and digits are allowed".into()));
+    }
+
+    Ok(ascii_str(address))
+}
+
+// Only called on hex digits and checked addresses
+fn ascii_str(bytes: &[u8]) -> &str {
+    std::str::from_utf8(bytes).expect("std::str::from_utf8() failed")
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+// Random (version 4) UUID in the 8-4-4-4-12 hex form
+fn uuid_v4() -> [u8; 36] {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let mut output = [b'-'; 36];
+    let mut i = 0;
+    for (j, b) in bytes.iter().enumerate() {
+        if matches!(j, 4 | 6 | 8 | 10) {
+            i += 1;
+        }
+        hex::encode_to_slice([*b], &mut output[i..i + 2])
+            .expect("hex::encode_to_slice() failed");
+        i += 2;
+    }
+
+    output
+}
+
+struct Counter(usize);
+
+impl Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_v3() {
+        let keystore = KeystoreInternal::new(
+            CipherParams::from_iv([1; 16]),
+            PlaintextKind::SecretKey,
+            &[2; 32],
+            KdfParamsInternal::from_parts(32, KdfInternal::Scrypt { n: 18, r: 8, p: 1 }, &[3; 16]).unwrap(),
+            [4; 32],
+        ).unwrap();
+        let address = b"0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+
+        let len = v3_len(&keystore, address, &Network::Ethereum).unwrap();
+        let mut output = Vec::new();
+        write_v3(&mut output, &keystore, address, &Network::Ethereum).unwrap();
+        let json = String::from_utf8(output).unwrap();
+
+        assert_eq!(json.len(), len);
+        assert!(json.starts_with("{\"address\":\"7e5f4552091a69125d5dfcb7b8c2659029395bdf\",\"crypto\":{"));
+        assert!(json.contains("\"kdfparams\":{\"dklen\":32,\"n\":262144,\"p\":1,\"r\":8,\"salt\":\"03030303030303030303030303030303\"}"));
+        assert!(json.ends_with("\"version\":3}"));
+
+        let id = &json[json.find("\"id\":\"").unwrap() + 6..][..36];
+        assert_eq!(id.as_bytes()[14], b'4');
+        assert_eq!(id.matches('-').count(), 4);
+
+        let parsed = parse_v3(json.as_bytes()).unwrap();
+        assert_eq!(parsed.mac_hex_encoded().unwrap(), keystore.mac_hex_encoded().unwrap());
+        assert_eq!(parsed.kdfparams.salt(), keystore.kdfparams.salt());
+        assert_eq!(parsed.plaintext_kind, PlaintextKind::SecretKey);
+
+        let address = b"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH\",\"version\":4,\"x\":\"";
+        let err = write_v3(&mut Vec::new(), &keystore, address, &Network::Bitcoin).err().unwrap();
+        assert!(matches!(err, KsgenError::InvalidAddressErr { .. }));
+    }
+
+    #[test]
+    fn test_write_v3_entropy() {
+        let keystore = KeystoreInternal::new(
+            CipherParams::from_iv([1; 16]),
+            PlaintextKind::Entropy,
+            &[2; 16],
+            KdfParamsInternal::from_parts(32, KdfInternal::Pbkdf2 { c: 1024 }, &[3; 16]).unwrap(),
+            [4; 32],
+        ).unwrap();
+
+        let mut output = Vec::new();
+        write_v3(&mut output, &keystore, b"", &Network::Bitcoin).unwrap();
+        let json = String::from_utf8(output).unwrap();
+
+        assert!(json.contains("\"ciphertext\":\"02020202020202020202020202020202\""));
+        assert!(json.ends_with("\"version\":3,\"x-ksgen\":{\"plaintext\":\"entropy\"}}"));
+
+        let parsed = parse_v3(json.as_bytes()).unwrap();
+        assert_eq!(parsed.plaintext_kind, PlaintextKind::Entropy);
+
+        let json = json.replace("\"entropy\"", "\"seed\"");
+        assert!(matches!(parse_v3(json.as_bytes()), Err(KsgenError::InvalidPlaintextErr { .. })));
+    }
+
+    #[test]
+    fn test_parse_v3() {
+        // Web3 Secret Storage test vector, the password is "testpassword"
+        let json = br#"{
+            "Crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {"iv": "83dbcc02d8ccb40e466191a123791e0e"},
+                "ciphertext": "d172bf743a674da9cdad04534d56926ef8358534d458fffccd4e6ad2fbde479c",
+                "kdf": "scrypt",
+                "kdfparams": {
+                    "dklen": 32,
+                    "n": 262144,
+                    "p": 8,
+                    "r": 1,
+                    "salt": "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+                },
+                "mac": "2103ac29920d71da29f15d75b4a16dbe95cfd7ff8faea1056c33131d846e3097"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3
+        }"#;
+
+        // scrypt requires n < 2^(16 * r), the vector can't be decrypted
+        let err = parse_v3(json).err().unwrap();
+        assert!(matches!(err, KsgenError::InvalidKdfParamErr { field_name, .. } if field_name == "log_n"));
+
+        let json = String::from_utf8_lossy(json).replace("\"r\": 1", "\"r\": 8").replace("\"p\": 8", "\"p\": 1");
+        let keystore = parse_v3(json.as_bytes()).unwrap();
+        assert_eq!(keystore.kdfparams.kdf, KdfInternal::Scrypt { n: 18, r: 8, p: 1 });
+        assert_eq!(keystore.kdfparams.salt().len(), 32);
+
+        let argon2_json = json.replace("\"kdf\": \"scrypt\"", "\"kdf\": \"argon2\"");
+        let err = parse_v3(argon2_json.as_bytes()).err().unwrap();
+        assert!(matches!(err, KsgenError::UnsupportedKdfErr { .. }));
+
+        // About 1 TiB of memory, rejected before the KDF runs
+        let huge_json = json.replace("\"n\": 262144", "\"n\": 1073741824");
+        let err = parse_v3(huge_json.as_bytes()).err().unwrap();
+        assert!(matches!(err, KsgenError::InvalidKdfParamErr { .. }));
+    }
+
+    #[test]
+    fn test_parse_v3_pbkdf2() {
+        // Web3 Secret Storage test vector, the password is "testpassword"
+        let json = br#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3
+        }"#;
+
+        let keystore = parse_v3(json).unwrap();
+        assert_eq!(keystore.kdfparams.kdf, KdfInternal::Pbkdf2 { c: 262144 });
+        assert_eq!(
+            hex::encode(keystore.decrypt_secret_key(b"testpassword").unwrap()),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d",
+        );
+
+        let prf_json = String::from_utf8_lossy(json).replace("hmac-sha256", "hmac-sha512");
+        let err = parse_v3(prf_json.as_bytes()).err().unwrap();
+        assert!(matches!(err, KsgenError::UnsupportedPrfErr { .. }));
+
+        let c_json = String::from_utf8_lossy(json).replace("\"c\": 262144", "\"c\": 4294967295");
+        let err = parse_v3(c_json.as_bytes()).err().unwrap();
+        assert!(matches!(err, KsgenError::InvalidKdfParamErr { .. }));
+
+        let dklen_json = String::from_utf8_lossy(json).replace("\"dklen\": 32", "\"dklen\": 16");
+        let err = parse_v3(dklen_json.as_bytes()).err().unwrap();
+        assert!(matches!(err, KsgenError::InvalidKdfParamErr { field_name, .. } if field_name == "dklen"));
+    }
+}
\ No newline at end of file
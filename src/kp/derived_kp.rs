@@ -1,12 +1,14 @@
+use zeroize::Zeroizing;
+
 use crate::error::Result;
 
 pub struct KeypairDerived<const N: usize> {
-    pub secret_key: [u8; 32],
+    pub secret_key: Zeroizing<[u8; 32]>,
     address: [u8; N],
 }
 
 impl<const N: usize> KeypairDerived<N> {
-    pub fn new(secret_key: [u8; 32], address: [u8; N]) -> Self {
+    pub fn new(secret_key: Zeroizing<[u8; 32]>, address: [u8; N]) -> Self {
         Self { secret_key, address }
     }
 
@@ -1,6 +1,8 @@
 use ed25519_dalek::{SigningKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
 use rand::rngs::OsRng;
+use zeroize::Zeroizing;
 
+// SigningKey wipes itself on drop with the default "zeroize" feature of ed25519-dalek
 pub struct Keypair(SigningKey);
 
 impl Keypair {
@@ -10,9 +12,14 @@ impl Keypair {
         Self(SigningKey::generate(&mut csprng))
     }
 
+    pub fn from_secret_key(secret_key: &[u8; SECRET_KEY_LENGTH]) -> Self {
+        // Any 32 bytes are a valid ed25519 seed
+        Self(SigningKey::from_bytes(secret_key))
+    }
+
     #[inline]
-    pub fn secret_key(&self) -> [u8; SECRET_KEY_LENGTH] {
-        self.0.to_bytes()
+    pub fn secret_key(&self) -> Zeroizing<[u8; SECRET_KEY_LENGTH]> {
+        Zeroizing::new(self.0.to_bytes())
     }
 
     #[inline]
@@ -3,5 +3,8 @@ pub mod derived_kp;
 #[cfg(any(feature = "aptos", feature = "solana", feature = "sui"))]
 pub mod ed25519_kp;
 
-#[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "tron"))]
+#[cfg(feature = "sui-secp256r1")]
+pub mod p256_kp;
+
+#[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "sui", feature = "tron"))]
 pub mod secp256k1_kp;
\ No newline at end of file
@@ -0,0 +1,32 @@
+use p256::{elliptic_curve::sec1::ToEncodedPoint, SecretKey};
+use rand::rngs::OsRng;
+use zeroize::Zeroizing;
+
+use crate::error::Result;
+
+// SecretKey wipes itself on drop
+pub struct Keypair(SecretKey);
+
+impl Keypair {
+    pub fn generate() -> Self {
+        Self(SecretKey::random(&mut OsRng))
+    }
+
+    pub fn from_secret_key(secret_key: &[u8; 32]) -> Result<Self> {
+        // Rejects zero and values outside of the curve order
+        Ok(Self(SecretKey::from_slice(secret_key)?))
+    }
+
+    #[inline]
+    pub fn secret_key(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.0.to_bytes().into())
+    }
+
+    #[inline]
+    pub fn public_key_compressed(&self) -> [u8; 33] {
+        let mut public_key = [0; 33];
+        public_key.copy_from_slice(self.0.public_key().to_encoded_point(true).as_bytes());
+
+        public_key
+    }
+}
\ No newline at end of file
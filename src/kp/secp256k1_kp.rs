@@ -1,6 +1,12 @@
 use rand::rngs::OsRng;
 use secp256k1::{PublicKey, Secp256k1, SecretKey};
 
+#[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "sui", feature = "tron"))]
+use secp256k1::Scalar;
+use zeroize::Zeroizing;
+
+use crate::error::Result;
+
 pub struct Keypair {
     secret_key: SecretKey,
     public_key: PublicKey,
@@ -14,13 +20,61 @@ impl Keypair {
         Self { secret_key, public_key }
     }
 
+    pub fn from_secret_key(secret_key: &[u8; 32]) -> Result<Self> {
+        // Rejects zero and values outside of the curve order
+        let secret_key = SecretKey::from_slice(secret_key)?;
+        let public_key = secret_key.public_key(&Secp256k1::signing_only());
+
+        Ok(Self { secret_key, public_key })
+    }
+
+    /// Adds the tweak to the secret key modulo the curve order, as BIP32 child keys do
+    #[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "sui", feature = "tron"))]
+    pub fn add_tweak(&self, tweak: &[u8; 32]) -> Result<Self> {
+        let tweak = Scalar::from_be_bytes(*tweak).map_err(|_| secp256k1::Error::InvalidTweak)?;
+        let secret_key = self.secret_key.add_tweak(&tweak)?;
+        let public_key = secret_key.public_key(&Secp256k1::signing_only());
+
+        Ok(Self { secret_key, public_key })
+    }
+
     #[inline]
-    pub fn secret_key(&self) -> [u8; 32] {
-        self.secret_key.secret_bytes()
+    pub fn secret_key(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.secret_key.secret_bytes())
     }
 
+    #[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "tron"))]
     #[inline]
     pub fn public_key(&self) -> [u8; 65] {
         self.public_key.serialize_uncompressed()
     }
+
+    #[inline]
+    pub fn public_key_compressed(&self) -> [u8; 33] {
+        self.public_key.serialize()
+    }
+
+    /// BIP340 x-only public key, the y coordinate is implicitly even
+    #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+    #[inline]
+    pub fn x_only_public_key(&self) -> [u8; 32] {
+        self.public_key.x_only_public_key().0.serialize()
+    }
+
+    /// Adds `tweak * G` to the x-only public key and returns the x-only result
+    #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+    pub fn tweak_x_only_public_key(&self, tweak: &[u8; 32]) -> Result<[u8; 32]> {
+        let tweak = Scalar::from_be_bytes(*tweak).map_err(|_| secp256k1::Error::InvalidTweak)?;
+        let (public_key, _) = self.public_key.x_only_public_key();
+        let (tweaked, _) = public_key.add_tweak(&Secp256k1::verification_only(), &tweak)?;
+
+        Ok(tweaked.serialize())
+    }
+}
+
+impl Drop for Keypair {
+    fn drop(&mut self) {
+        // secp256k1 has no zeroize support, overwrites the key with a constant instead
+        self.secret_key.non_secure_erase();
+    }
 }
\ No newline at end of file
@@ -1,26 +1,511 @@
 mod chains;
 mod kp;
 mod error;
+mod hd;
 mod keystore;
 mod keystore_ffi;
+mod keystore_json;
 mod macros;
+mod mnemonic;
 
 #[cfg(any(feature = "bitcoin", feature = "litecoin", feature = "tron"))]
 mod bs58_check;
 
+#[cfg(any(feature = "bitcoin", feature = "litecoin", feature = "sui"))]
+mod bech32;
+
 use std::{ffi::{c_char, CString}, ptr::null};
 
+use zeroize::Zeroizing;
+
+use hd::path::DerivationPathInternal;
 use keystore::KeystoreInternal;
-use keystore_ffi::{Keystore, Network, SizedStr, SizedStrConst};
+use keystore_ffi::{
+    AddressOptions, AddressOptionsInternal, DerivationPath, DerivationPathMut, KdfOptions, Keystore, Network, PlaintextKind, SizedBytes,
+    SizedBytesConst, SizedStr, SizedStrConst, SolanaKeypairFormat, SuiPrivateKeyFormat, DERIVATION_PATH_LEN, MNEMONIC_LEN,
+    SECRET_KEY_LEN, SEED_LEN, WIF_LEN,
+};
 
 type ErrorPtr = *const c_char;
 
+/// Generates a key of the network and encrypts it into a new keystore with the default address
+/// format and KDF
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens, `address`
+/// must be writable for its len and `password` readable for its len
 #[no_mangle]
 pub unsafe extern "C" fn init_keystore(
     keystore: *mut Keystore,
     address: SizedStr,
     password: SizedStrConst,
     network: Network,
+) -> ErrorPtr {
+    init_keystore_with_options(keystore, address, password, network, null(), null())
+}
+
+/// Same as `init_keystore`, with the address format and the KDF picked by the caller
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens, `address`
+/// must be writable for its len and `password` readable for its len.
+/// `address_options` and `kdf_options` may be null, otherwise they must point to valid options
+#[no_mangle]
+pub unsafe extern "C" fn init_keystore_with_options(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    network: Network,
+    address_options: *const AddressOptions,
+    kdf_options: *const KdfOptions,
+) -> ErrorPtr {
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+    init_keystore_internal(keystore, address, password, network, address_options, kdf_options, None, None)
+}
+
+/// Same as `init_keystore`, also writes the generated Bitcoin or Litecoin key in the Wallet Import Format
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens, `address`
+/// and `wif` must be writable for their lens and `password` readable for its len.
+/// `address_options` and `kdf_options` may be null, otherwise they must point to valid options
+#[no_mangle]
+pub unsafe extern "C" fn init_keystore_with_wif(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    network: Network,
+    address_options: *const AddressOptions,
+    kdf_options: *const KdfOptions,
+    wif: SizedStr,
+) -> ErrorPtr {
+    unwrap_or_handle!(wif.validate(WIF_LEN, "wif"));
+
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+    init_keystore_internal(keystore, address, password, network, address_options, kdf_options, None, Some(wif))
+}
+
+/// Imports a raw 32-byte secret key into a new keystore of the network
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens and
+/// `address` must be writable for its len. `password` and `secret_key` must be readable for their
+/// lens. `address_options` and `kdf_options` may be null, otherwise they must point to valid options
+#[no_mangle]
+pub unsafe extern "C" fn init_keystore_from_secret_key(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    network: Network,
+    address_options: *const AddressOptions,
+    kdf_options: *const KdfOptions,
+    secret_key: SizedBytesConst,
+) -> ErrorPtr {
+    unwrap_or_handle!(secret_key.validate(SECRET_KEY_LEN, "secret_key"));
+
+    let mut secret_key_bytes = Zeroizing::new([0; SECRET_KEY_LEN]);
+    secret_key_bytes.copy_from_slice(secret_key.as_slice());
+
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+    init_keystore_internal(keystore, address, password, network, address_options, kdf_options, Some(secret_key_bytes), None)
+}
+
+/// Imports a Bitcoin or Litecoin key in the Wallet Import Format. The default address kind follows
+/// the compression flag of the key, explicit kinds must match it
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens and
+/// `address` must be writable for its len. `password` and `wif` must be readable for their lens.
+/// `address_options` and `kdf_options` may be null, otherwise they must point to valid options
+#[no_mangle]
+#[cfg_attr(not(any(feature = "bitcoin", feature = "litecoin")), allow(unused_variables, unreachable_code))]
+pub unsafe extern "C" fn init_keystore_from_wif(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    network: Network,
+    address_options: *const AddressOptions,
+    kdf_options: *const KdfOptions,
+    wif: SizedStrConst,
+) -> ErrorPtr {
+    unwrap_or_handle!(wif.validate("wif"));
+
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+    unwrap_or_handle!(address_options.validate(&network));
+
+    let (secret_key, address_options) = match network {
+        #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+        Network::Bitcoin | Network::Litecoin => {
+            use chains::bitcoin;
+            use keystore_ffi::AddressKind;
+
+            let params = unwrap_or_handle_ctx!(
+                address_options.bitcoin_network_params(&network),
+                "err address_options.bitcoin_network_params()",
+            );
+            let (secret_key, compressed) = unwrap_or_handle_ctx!(
+                bitcoin::decode_wif(wif.as_slice(), params),
+                "err bitcoin::decode_wif()",
+            );
+
+            let mut address_options = address_options;
+            if !compressed && matches!(address_options.kind, AddressKind::Default) {
+                address_options.kind = AddressKind::P2pkhUncompressed;
+            }
+            if address_options.kind.bitcoin_address_type().is_compressed() != compressed {
+                bail!("the address kind ({:?}) doesn't match the WIF compression flag ({})", address_options.kind, compressed);
+            }
+
+            (secret_key, address_options)
+        },
+
+        _ => bail!("WIF is not supported by the network ({:?}); try building the library using available features", network),
+    };
+
+    init_keystore_internal(keystore, address, password, network, address_options, kdf_options, Some(secret_key), None)
+}
+
+/// Imports a solana-keygen compatible 64-byte keypair (seed followed by the public key),
+/// the embedded public key must match the seed
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens and
+/// `address` must be writable for its len. `password` and `keypair` must be readable for their lens,
+/// `kdf_options` may be null or point to valid options
+#[no_mangle]
+#[cfg_attr(not(feature = "solana"), allow(unused_variables))]
+pub unsafe extern "C" fn init_keystore_from_solana_keypair(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    kdf_options: *const KdfOptions,
+    format: u32,
+    keypair: SizedStrConst,
+) -> ErrorPtr {
+    unwrap_or_handle!(keypair.validate("keypair"));
+
+    let format = unwrap_or_handle!(SolanaKeypairFormat::try_from(format));
+
+    #[cfg(not(feature = "solana"))]
+    bail!("the library was built without the network ({:?}); enable the `solana` feature", Network::Solana);
+
+    #[cfg(feature = "solana")]
+    {
+        use chains::solana;
+
+        let keypair = keypair.as_slice();
+        let secret_key = unwrap_or_handle_ctx!(
+            match format {
+                SolanaKeypairFormat::Json => solana::import_keypair_json(keypair),
+                SolanaKeypairFormat::Base58 => solana::import_keypair_bs58(keypair),
+            },
+            "err solana::import_keypair()",
+        );
+
+        let address_options = AddressOptionsInternal::default();
+        init_keystore_internal(keystore, address, password, Network::Solana, address_options, kdf_options, Some(secret_key), None)
+    }
+}
+
+/// Imports a Sui private key in the Bech32 `suiprivkey` or `sui.keystore` Base64 format,
+/// the address follows the signature scheme flag of the key
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens and
+/// `address` must be writable for its len. `password` and `private_key` must be readable for their
+/// lens, `kdf_options` may be null or point to valid options
+#[no_mangle]
+#[cfg_attr(not(feature = "sui"), allow(unused_variables))]
+pub unsafe extern "C" fn init_keystore_from_sui_private_key(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    kdf_options: *const KdfOptions,
+    format: u32,
+    private_key: SizedStrConst,
+) -> ErrorPtr {
+    unwrap_or_handle!(private_key.validate("private_key"));
+
+    let format = unwrap_or_handle!(SuiPrivateKeyFormat::try_from(format));
+
+    #[cfg(not(feature = "sui"))]
+    bail!("the library was built without the network ({:?}); enable the `sui` feature", Network::Sui);
+
+    #[cfg(feature = "sui")]
+    {
+        use chains::sui;
+        use keystore_ffi::AddressKind;
+
+        let private_key = private_key.as_slice();
+        let (secret_key, scheme) = unwrap_or_handle_ctx!(
+            match format {
+                SuiPrivateKeyFormat::Bech32 => sui::import_private_key_bech32(private_key),
+                SuiPrivateKeyFormat::Base64 => sui::import_private_key_base64(private_key),
+            },
+            "err sui::import_private_key()",
+        );
+
+        let address_options = AddressOptionsInternal {
+            kind: AddressKind::from_sui_signature_scheme(scheme),
+            ..Default::default()
+        };
+
+        init_keystore_internal(keystore, address, password, Network::Sui, address_options, kdf_options, Some(secret_key), None)
+    }
+}
+
+/// Derives the key at the path from a BIP39 phrase and an optional (empty) passphrase. Secp256k1
+/// networks use BIP32, ed25519 networks use SLIP-0010 and accept only hardened indexes
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens and
+/// `address` must be writable for its len. `password`, `mnemonic` and `passphrase` must be readable
+/// for their lens and `path` must hold `path.len` indexes. `address_options` and `kdf_options` may be
+/// null, otherwise they must point to valid options
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(
+    not(any(
+        feature = "aptos", feature = "bitcoin", feature = "ethereum", feature = "litecoin",
+        feature = "solana", feature = "sui", feature = "tron",
+    )),
+    allow(unused_variables, unreachable_code),
+)]
+pub unsafe extern "C" fn init_keystore_from_mnemonic(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    network: Network,
+    address_options: *const AddressOptions,
+    kdf_options: *const KdfOptions,
+    mnemonic: SizedStrConst,
+    passphrase: SizedStrConst,
+    path: DerivationPath,
+) -> ErrorPtr {
+    unwrap_or_handle!(mnemonic.validate("mnemonic"));
+    unwrap_or_handle!(passphrase.validate("passphrase"));
+    unwrap_or_handle!(path.validate("path"));
+
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+
+    // Unlike mnemonic_to_seed(), a mistyped phrase must not silently lead to another key
+    unwrap_or_handle_ctx!(mnemonic::to_entropy(mnemonic.as_slice()), "err mnemonic::to_entropy()");
+
+    let seed = unwrap_or_handle_ctx!(
+        mnemonic::to_seed(mnemonic.as_slice(), passphrase.as_slice()),
+        "err mnemonic::to_seed()",
+    );
+
+    let secret_key = match network {
+        #[cfg(any(feature = "bitcoin", feature = "ethereum", feature = "litecoin", feature = "tron"))]
+        Network::Bitcoin | Network::Ethereum | Network::Litecoin | Network::Tron => {
+            let kp = unwrap_or_handle_ctx!(
+                hd::bip32::derive_keypair(seed.as_slice(), path.as_slice()),
+                "err bip32::derive_keypair()",
+            );
+
+            kp.secret_key()
+        },
+
+        // Sui derives secp256k1 keys with BIP32, like the m/54'/784'/... paths of its wallets
+        #[cfg(feature = "sui")]
+        Network::Sui if matches!(address_options.kind, keystore_ffi::AddressKind::Secp256k1) => {
+            let kp = unwrap_or_handle_ctx!(
+                hd::bip32::derive_keypair(seed.as_slice(), path.as_slice()),
+                "err bip32::derive_keypair()",
+            );
+
+            kp.secret_key()
+        },
+
+        #[cfg(any(feature = "aptos", feature = "solana", feature = "sui"))]
+        Network::Aptos | Network::Solana | Network::Sui => {
+            use keystore_ffi::AddressKind;
+
+            if !matches!(address_options.kind, AddressKind::Default) {
+                bail!("the address kind ({:?}) can't be derived from a mnemonic for the network ({:?})", address_options.kind, network);
+            }
+
+            let kp = unwrap_or_handle_ctx!(
+                hd::slip10::derive_keypair(seed.as_slice(), path.as_slice()),
+                "err slip10::derive_keypair()",
+            );
+
+            kp.secret_key()
+        },
+
+        // Reachable only when some of the networks above are compiled out
+        #[allow(unreachable_patterns)]
+        _ => bail!("mnemonic derivation is not supported by the network ({:?}); try building the library using available features", network),
+    };
+
+    init_keystore_internal(keystore, address, password, network, address_options, kdf_options, Some(secret_key), None)
+}
+
+/// Encrypts a whole BIP39 phrase instead of a derived key, either as its entropy or as the phrase
+/// itself with the words separated by single spaces
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens, with a
+/// non-null `plaintext_kind`. `password` and `mnemonic` must be readable for their lens,
+/// `kdf_options` may be null or point to valid options
+#[no_mangle]
+pub unsafe extern "C" fn init_mnemonic_keystore(
+    keystore: *mut Keystore,
+    password: SizedStrConst,
+    kdf_options: *const KdfOptions,
+    plaintext_kind: u32,
+    mnemonic: SizedStrConst,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate_mnemonic());
+    unwrap_or_handle!(password.validate("password"));
+    unwrap_or_handle!(mnemonic.validate("mnemonic"));
+
+    let plaintext_kind = unwrap_or_handle!(PlaintextKind::try_from(plaintext_kind));
+
+    let (entropy, entropy_len) = unwrap_or_handle_ctx!(
+        mnemonic::to_entropy(mnemonic.as_slice()),
+        "err mnemonic::to_entropy()",
+    );
+    let entropy = &entropy[..entropy_len];
+
+    let phrase = match plaintext_kind {
+        PlaintextKind::Entropy => None,
+        PlaintextKind::Mnemonic => Some(unwrap_or_handle_ctx!(mnemonic::from_entropy(entropy), "err mnemonic::from_entropy()")),
+        PlaintextKind::SecretKey => bail!("a mnemonic can't be stored as {:?}; derive a key with init_keystore_from_mnemonic()", plaintext_kind),
+    };
+    let plaintext = phrase.as_ref().map_or(entropy, |(phrase, phrase_len)| &phrase[..*phrase_len]);
+
+    let password = password.as_slice();
+    let kdf = unwrap_or_handle_ctx!(KdfOptions::read_kdf_internal(kdf_options), "err KdfOptions::read_kdf_internal()")
+        .unwrap_or_default();
+
+    let keystore_internal = unwrap_or_handle_ctx!(
+        KeystoreInternal::from_plaintext(plaintext_kind, plaintext, password, kdf),
+        "err KeystoreInternal::from_plaintext()",
+    );
+    unwrap_or_handle_ctx!(keystore.write(keystore_internal), "err keystore.write()");
+
+    null()
+}
+
+/// Writes the conventional derivation path of the account (or address) index, Phantom for Solana,
+/// Sui Wallet for Sui and Petra for Aptos, returns its len in `path_len`
+///
+/// # Safety
+///
+/// `path` must be writable for `path.len` indexes and `path_len` must be valid for writes
+#[no_mangle]
+#[cfg_attr(not(any(feature = "aptos", feature = "solana", feature = "sui")), allow(unused_variables, unreachable_code))]
+pub unsafe extern "C" fn default_derivation_path(
+    network: Network,
+    index: u32,
+    path: DerivationPathMut,
+    path_len: *mut usize,
+) -> ErrorPtr {
+    unwrap_or_handle!(path.validate(DERIVATION_PATH_LEN, "path"));
+    unwrap_or_handle!(keystore_ffi::ptr_is_null(path_len, "path_len"));
+
+    if index & hd::HARDENED != 0 {
+        bail!("the index ({}) must be lower than 2^31", index);
+    }
+
+    *path_len = match network {
+        #[cfg(feature = "aptos")]
+        Network::Aptos => path.write_indexes(&chains::aptos::derivation_path(index)),
+
+        #[cfg(feature = "solana")]
+        Network::Solana => path.write_indexes(&chains::solana::derivation_path(index)),
+
+        #[cfg(feature = "sui")]
+        Network::Sui => path.write_indexes(&chains::sui::derivation_path(index)),
+
+        _ => bail!("no default derivation path for the network ({:?}); try building the library using available features", network),
+    };
+
+    null()
+}
+
+/// Parses a path like `m/84'/0'/0'/0/5` into `path`, returns its len in `path_len`. Ed25519
+/// keys (Aptos, Solana and the default Sui kind) accept only hardened segments
+///
+/// # Safety
+///
+/// `input` must be readable for its len, `path` writable for `path.len` indexes and `path_len`
+/// valid for writes. `address_options` may be null or point to valid options
+#[no_mangle]
+pub unsafe extern "C" fn parse_derivation_path(
+    network: Network,
+    address_options: *const AddressOptions,
+    input: SizedStrConst,
+    path: DerivationPathMut,
+    path_len: *mut usize,
+) -> ErrorPtr {
+    unwrap_or_handle!(input.validate("input"));
+    unwrap_or_handle!(keystore_ffi::ptr_is_null(path_len, "path_len"));
+
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+    unwrap_or_handle!(address_options.validate(&network));
+
+    let parsed = unwrap_or_handle_ctx!(
+        DerivationPathInternal::parse(input.as_slice()),
+        "err DerivationPathInternal::parse()",
+    );
+    unwrap_or_handle!(parsed.validate(&network, &address_options));
+    unwrap_or_handle!(path.validate_capacity(parsed.as_slice().len(), "path"));
+
+    *path_len = path.write_indexes(parsed.as_slice());
+
+    null()
+}
+
+/// Writes the BIP43 purpose of the address kind and the SLIP-44 coin type of the network, the
+/// coin type is 1 on test chains
+///
+/// # Safety
+///
+/// `purpose` and `coin_type` must be valid for writes, `address_options` may be null or point
+/// to valid options
+#[no_mangle]
+pub unsafe extern "C" fn derivation_purpose_and_coin_type(
+    network: Network,
+    address_options: *const AddressOptions,
+    purpose: *mut u32,
+    coin_type: *mut u32,
+) -> ErrorPtr {
+    unwrap_or_handle!(keystore_ffi::ptr_is_null(purpose, "purpose"));
+    unwrap_or_handle!(keystore_ffi::ptr_is_null(coin_type, "coin_type"));
+
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+    unwrap_or_handle!(address_options.validate(&network));
+
+    *purpose = hd::path::purpose(&network, address_options.kind);
+    *coin_type = hd::path::coin_type(&network, address_options.chain);
+
+    null()
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe fn init_keystore_internal(
+    keystore: *mut Keystore,
+    address: SizedStr,
+    password: SizedStrConst,
+    network: Network,
+    address_options: AddressOptionsInternal,
+    kdf_options: *const KdfOptions,
+    secret_key: Option<Zeroizing<[u8; SECRET_KEY_LEN]>>,
+    wif: Option<SizedStr>,
 ) -> ErrorPtr {
     const ADDRESS_FIELD_NAME: &str = "address";
 
@@ -28,6 +513,11 @@ pub unsafe extern "C" fn init_keystore(
 
     unwrap_or_handle!(keystore.validate());
     unwrap_or_handle!(password.validate("password"));
+    unwrap_or_handle!(address_options.validate(&network));
+
+    if wif.is_some() && !matches!(network, Network::Bitcoin | Network::Litecoin) {
+        bail!("WIF is not supported by the network ({:?})", network);
+    }
 
     let secret_key = match network {
         #[cfg(feature = "aptos")]
@@ -36,7 +526,10 @@ pub unsafe extern "C" fn init_keystore(
 
             unwrap_or_handle!(address.validate(aptos::ADDRESS_LEN, ADDRESS_FIELD_NAME));
 
-            let kp = unwrap_or_handle_ctx!(aptos::get_keypair(), "err aptos::get_keypair()");
+            let kp = unwrap_or_handle_ctx!(
+                secret_key.as_deref().map_or_else(aptos::get_keypair, aptos::keypair_from_secret),
+                "err aptos::get_keypair()",
+            );
             let address_slice = unwrap_or_handle_ctx!(kp.address_ref_fixed(), "err kp.address_ref_fixed()");
 
             address.write_bytes(address_slice);
@@ -48,9 +541,29 @@ pub unsafe extern "C" fn init_keystore(
         Network::Bitcoin => {
             use chains::bitcoin;
 
-            unwrap_or_handle!(address.validate(bitcoin::ADDRESS_LEN, ADDRESS_FIELD_NAME));
+            let address_type = address_options.kind.bitcoin_address_type();
+            let params = unwrap_or_handle_ctx!(
+                address_options.bitcoin_network_params(&network),
+                "err address_options.bitcoin_network_params()",
+            );
+
+            unwrap_or_handle!(address.validate(address_type.address_len(params), ADDRESS_FIELD_NAME));
 
-            let kp = unwrap_or_handle_ctx!(bitcoin::get_keypair(), "err bitcoin::get_keypair()");
+            let kp = unwrap_or_handle_ctx!(
+                secret_key.as_deref().map_or_else(
+                    || bitcoin::get_keypair(address_type, params),
+                    |secret_key| bitcoin::keypair_from_secret(secret_key, address_type, params),
+                ),
+                "err bitcoin::get_keypair()",
+            );
+
+            if let Some(wif) = &wif {
+                let (wif_encoded, wif_len) = unwrap_or_handle_ctx!(
+                    bitcoin::encode_wif(&kp.secret_key, params, address_type.is_compressed()),
+                    "err bitcoin::encode_wif()",
+                );
+                wif.write_bytes(&wif_encoded[..wif_len]);
+            }
             let address_slice = unwrap_or_handle_ctx!(kp.address_ref(), "err kp.address_ref_fixed()");
 
             address.write_bytes(address_slice);
@@ -62,10 +575,17 @@ pub unsafe extern "C" fn init_keystore(
         Network::Ethereum => {
             use chains::ethereum;
 
-            unwrap_or_handle!(address.validate(ethereum::ADDRESS_LEN, ADDRESS_FIELD_NAME));
+            let format = address_options.ethereum_address_format();
+            unwrap_or_handle!(address.validate(format.address_len(), ADDRESS_FIELD_NAME));
 
-            let kp = unwrap_or_handle_ctx!(ethereum::get_keypair(), "err ethereum::get_keypair()");
-            let address_slice = unwrap_or_handle_ctx!(kp.address_ref_fixed(), "err kp.address_ref_fixed()");
+            let kp = unwrap_or_handle_ctx!(
+                secret_key.as_deref().map_or_else(
+                    || ethereum::get_keypair(format),
+                    |secret_key| ethereum::keypair_from_secret(secret_key, format),
+                ),
+                "err ethereum::get_keypair()",
+            );
+            let address_slice = unwrap_or_handle_ctx!(kp.address_ref(), "err kp.address_ref()");
 
             address.write_bytes(address_slice);
 
@@ -74,11 +594,31 @@ pub unsafe extern "C" fn init_keystore(
 
         #[cfg(feature = "litecoin")]
         Network::Litecoin => {
-            use chains::litecoin;
+            use chains::{bitcoin, litecoin};
+
+            let address_type = address_options.kind.bitcoin_address_type();
+            let params = unwrap_or_handle_ctx!(
+                address_options.bitcoin_network_params(&network),
+                "err address_options.bitcoin_network_params()",
+            );
+
+            unwrap_or_handle!(address.validate(address_type.address_len(params), ADDRESS_FIELD_NAME));
 
-            unwrap_or_handle!(address.validate(litecoin::ADDRESS_LEN, ADDRESS_FIELD_NAME));
+            let kp = unwrap_or_handle_ctx!(
+                secret_key.as_deref().map_or_else(
+                    || litecoin::get_keypair(address_type, params),
+                    |secret_key| litecoin::keypair_from_secret(secret_key, address_type, params),
+                ),
+                "err litecoin::get_keypair()",
+            );
 
-            let kp = unwrap_or_handle_ctx!(litecoin::get_keypair(), "err litecoin::get_keypair()");
+            if let Some(wif) = &wif {
+                let (wif_encoded, wif_len) = unwrap_or_handle_ctx!(
+                    bitcoin::encode_wif(&kp.secret_key, params, address_type.is_compressed()),
+                    "err bitcoin::encode_wif()",
+                );
+                wif.write_bytes(&wif_encoded[..wif_len]);
+            }
             let address_slice = unwrap_or_handle_ctx!(kp.address_ref(), "err kp.address_ref_fixed()");
 
             address.write_bytes(address_slice);
@@ -92,7 +632,10 @@ pub unsafe extern "C" fn init_keystore(
 
             unwrap_or_handle!(address.validate(solana::ADDRESS_LEN, ADDRESS_FIELD_NAME));
 
-            let kp = unwrap_or_handle_ctx!(solana::get_keypair(), "err solana::get_keypair()");
+            let kp = unwrap_or_handle_ctx!(
+                secret_key.as_deref().map_or_else(solana::get_keypair, solana::keypair_from_secret),
+                "err solana::get_keypair()",
+            );
             let address_slice = unwrap_or_handle_ctx!(kp.address_ref(), "err kp.address_ref()");
 
             address.write_bytes(address_slice);
@@ -106,7 +649,14 @@ pub unsafe extern "C" fn init_keystore(
 
             unwrap_or_handle!(address.validate(sui::ADDRESS_LEN, ADDRESS_FIELD_NAME));
 
-            let kp = unwrap_or_handle_ctx!(sui::get_keypair(), "err sui::get_keypair()");
+            let scheme = address_options.kind.sui_signature_scheme();
+            let kp = unwrap_or_handle_ctx!(
+                secret_key.as_deref().map_or_else(
+                    || sui::get_keypair(scheme),
+                    |secret_key| sui::keypair_from_secret(secret_key, scheme),
+                ),
+                "err sui::get_keypair()",
+            );
             let address_slice = unwrap_or_handle_ctx!(kp.address_ref_fixed(), "err kp.address_ref_fixed()");
 
             address.write_bytes(address_slice);
@@ -120,7 +670,10 @@ pub unsafe extern "C" fn init_keystore(
 
             unwrap_or_handle!(address.validate(tron::ADDRESS_LEN, ADDRESS_FIELD_NAME));
 
-            let kp = unwrap_or_handle_ctx!(tron::get_keypair(), "err tron::get_keypair()");
+            let kp = unwrap_or_handle_ctx!(
+                secret_key.as_deref().map_or_else(tron::get_keypair, tron::keypair_from_secret),
+                "err tron::get_keypair()",
+            );
             let address_slice = unwrap_or_handle_ctx!(kp.address_ref_fixed(), "err kp.address_ref_fixed()");
 
             address.write_bytes(address_slice);
@@ -128,13 +681,17 @@ pub unsafe extern "C" fn init_keystore(
             kp.secret_key
         },
 
+        // Reachable only when some of the networks above are compiled out
+        #[allow(unreachable_patterns)]
         _ => bail!("an unknown network ({:?}) was passed as a parameter; try building the library using available features", network),
     };
 
     let password = password.as_slice();
+    let kdf = unwrap_or_handle_ctx!(KdfOptions::read_kdf_internal(kdf_options), "err KdfOptions::read_kdf_internal()")
+        .unwrap_or_default();
 
     let keystore_internal = unwrap_or_handle_ctx!(
-        KeystoreInternal::from_secret_key(secret_key, password),
+        KeystoreInternal::from_secret_key(secret_key, password, kdf),
         "err KeystoreInternal::from_secret_key()",
     );
     unwrap_or_handle_ctx!(keystore.write(keystore_internal), "err keystore.write()");
@@ -142,6 +699,475 @@ pub unsafe extern "C" fn init_keystore(
     null()
 }
 
+/// Decrypts the 32-byte secret key of a keystore, a wrong password fails on the MAC check
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are readable for their lens, `password` must
+/// be readable for its len and `secret_key` writable for its len
+#[no_mangle]
+pub unsafe extern "C" fn decrypt_keystore(
+    keystore: *const Keystore,
+    password: SizedStrConst,
+    secret_key: SizedBytes,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(password.validate("password"));
+    unwrap_or_handle!(secret_key.validate(SECRET_KEY_LEN, "secret_key"));
+
+    let password = password.as_slice();
+
+    let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+    let secret_key_slice = unwrap_or_handle_ctx!(
+        keystore_internal.decrypt_secret_key(password),
+        "err keystore_internal.decrypt_secret_key()",
+    );
+    secret_key.write_bytes(secret_key_slice.as_slice());
+
+    null()
+}
+
+/// Decrypts a Bitcoin or Litecoin keystore into the Wallet Import Format. The compression flag
+/// follows the address kind, so pass the same `address_options` the keystore was created with
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are readable for their lens, `password` must
+/// be readable for its len and `wif` writable for its len. `address_options` may be null or point to
+/// valid options
+#[no_mangle]
+pub unsafe extern "C" fn decrypt_keystore_to_wif(
+    keystore: *const Keystore,
+    password: SizedStrConst,
+    network: Network,
+    address_options: *const AddressOptions,
+    wif: SizedStr,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(password.validate("password"));
+    unwrap_or_handle!(wif.validate(WIF_LEN, "wif"));
+
+    let address_options = unwrap_or_handle!(AddressOptions::read(address_options));
+    unwrap_or_handle!(address_options.validate(&network));
+
+    match network {
+        #[cfg(any(feature = "bitcoin", feature = "litecoin"))]
+        Network::Bitcoin | Network::Litecoin => {
+            use chains::bitcoin;
+
+            let params = unwrap_or_handle_ctx!(
+                address_options.bitcoin_network_params(&network),
+                "err address_options.bitcoin_network_params()",
+            );
+
+            let password = password.as_slice();
+
+            let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+            let secret_key = unwrap_or_handle_ctx!(
+                keystore_internal.decrypt_secret_key(password),
+                "err keystore_internal.decrypt_secret_key()",
+            );
+
+            let compressed = address_options.kind.bitcoin_address_type().is_compressed();
+            let (wif_encoded, wif_len) = unwrap_or_handle_ctx!(
+                bitcoin::encode_wif(&secret_key, params, compressed),
+                "err bitcoin::encode_wif()",
+            );
+            wif.write_bytes(&wif_encoded[..wif_len]);
+
+            null()
+        },
+
+        _ => bail!("WIF is not supported by the network ({:?}); try building the library using available features", network),
+    }
+}
+
+/// Decrypts a Solana keystore into the 64-byte keypair of solana-keygen, either as the `id.json`
+/// byte array or as a Base58 string. The buffer len must match the format
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are readable for their lens, `password` must
+/// be readable for its len and `keypair` writable for its len
+#[no_mangle]
+#[cfg_attr(not(feature = "solana"), allow(unused_variables))]
+pub unsafe extern "C" fn decrypt_keystore_to_solana_keypair(
+    keystore: *const Keystore,
+    password: SizedStrConst,
+    format: u32,
+    keypair: SizedStr,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(password.validate("password"));
+
+    let format = unwrap_or_handle!(SolanaKeypairFormat::try_from(format));
+    unwrap_or_handle!(keypair.validate(format.buffer_len(), "keypair"));
+
+    #[cfg(not(feature = "solana"))]
+    bail!("the library was built without the network ({:?}); enable the `solana` feature", Network::Solana);
+
+    #[cfg(feature = "solana")]
+    {
+        use chains::solana;
+
+        let password = password.as_slice();
+
+        let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+        let secret_key = unwrap_or_handle_ctx!(
+            keystore_internal.decrypt_secret_key(password),
+            "err keystore_internal.decrypt_secret_key()",
+        );
+
+        match format {
+            SolanaKeypairFormat::Json => {
+                let (keypair_encoded, keypair_len) = unwrap_or_handle_ctx!(
+                    solana::export_keypair_json(&secret_key),
+                    "err solana::export_keypair_json()",
+                );
+                keypair.write_bytes(&keypair_encoded[..keypair_len]);
+            },
+            SolanaKeypairFormat::Base58 => {
+                let (keypair_encoded, keypair_len) = unwrap_or_handle_ctx!(
+                    solana::export_keypair_bs58(&secret_key),
+                    "err solana::export_keypair_bs58()",
+                );
+                keypair.write_bytes(&keypair_encoded[..keypair_len]);
+            },
+        }
+
+        null()
+    }
+}
+
+/// Decrypts a Sui keystore into the Bech32 `suiprivkey` string or a `sui.keystore` Base64 entry.
+/// The buffer len must match the format, the flag follows the signature scheme that derives `address`
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are readable for their lens, `password` and
+/// `address` must be readable for their lens and `private_key` writable for its len
+#[no_mangle]
+#[cfg_attr(not(feature = "sui"), allow(unused_variables))]
+pub unsafe extern "C" fn decrypt_keystore_to_sui_private_key(
+    keystore: *const Keystore,
+    password: SizedStrConst,
+    address: SizedStrConst,
+    format: u32,
+    private_key: SizedStr,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(password.validate("password"));
+    unwrap_or_handle!(address.validate("address"));
+
+    let format = unwrap_or_handle!(SuiPrivateKeyFormat::try_from(format));
+    unwrap_or_handle!(private_key.validate(format.buffer_len(), "private_key"));
+
+    #[cfg(not(feature = "sui"))]
+    bail!("the library was built without the network ({:?}); enable the `sui` feature", Network::Sui);
+
+    #[cfg(feature = "sui")]
+    {
+        use chains::sui;
+
+        let password = password.as_slice();
+
+        let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+        let secret_key = unwrap_or_handle_ctx!(
+            keystore_internal.decrypt_secret_key(password),
+            "err keystore_internal.decrypt_secret_key()",
+        );
+
+        let scheme = unwrap_or_handle_ctx!(
+            sui::signature_scheme_of(&secret_key, address.as_slice()),
+            "err sui::signature_scheme_of()",
+        );
+        match format {
+            SuiPrivateKeyFormat::Bech32 => {
+                let private_key_encoded = unwrap_or_handle_ctx!(
+                    sui::export_private_key_bech32(&secret_key, scheme),
+                    "err sui::export_private_key_bech32()",
+                );
+                private_key.write_bytes(private_key_encoded.as_slice());
+            },
+            SuiPrivateKeyFormat::Base64 => {
+                let private_key_encoded = unwrap_or_handle_ctx!(
+                    sui::export_private_key_base64(&secret_key, scheme),
+                    "err sui::export_private_key_base64()",
+                );
+                private_key.write_bytes(private_key_encoded.as_slice());
+            },
+        }
+
+        null()
+    }
+}
+
+/// Decrypts a keystore of `init_mnemonic_keystore` into the BIP39 phrase, whether it holds the
+/// entropy or the phrase
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are readable for their lens, a null
+/// `plaintext_kind` reads as a secret key. `password` must be readable for its len and `mnemonic`
+/// writable for its len
+#[no_mangle]
+pub unsafe extern "C" fn decrypt_keystore_to_mnemonic(
+    keystore: *const Keystore,
+    password: SizedStrConst,
+    mnemonic: SizedStr,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(password.validate("password"));
+    unwrap_or_handle!(mnemonic.validate(MNEMONIC_LEN, "mnemonic"));
+
+    let password = password.as_slice();
+
+    let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+    if keystore_internal.plaintext_kind == PlaintextKind::SecretKey {
+        bail!("the keystore holds {:?}, not a mnemonic", keystore_internal.plaintext_kind);
+    }
+
+    let plaintext = unwrap_or_handle_ctx!(
+        keystore_internal.decrypt(password),
+        "err keystore_internal.decrypt()",
+    );
+
+    match plaintext.kind {
+        PlaintextKind::Entropy => {
+            let (phrase, phrase_len) = unwrap_or_handle_ctx!(
+                mnemonic::from_entropy(plaintext.as_slice()),
+                "err mnemonic::from_entropy()",
+            );
+            mnemonic.write_bytes(&phrase[..phrase_len]);
+        },
+        _ => mnemonic.write_bytes(plaintext.as_slice()),
+    }
+
+    null()
+}
+
+/// Checks the password against the MAC, a wrong one is not an error and sets `is_valid` to false
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are readable for their lens, `password` must
+/// be readable for its len and `is_valid` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn verify_keystore_password(
+    keystore: *const Keystore,
+    password: SizedStrConst,
+    is_valid: *mut bool,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(password.validate("password"));
+    unwrap_or_handle!(keystore_ffi::ptr_is_null(is_valid, "is_valid"));
+
+    let password = password.as_slice();
+
+    let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+    *is_valid = unwrap_or_handle_ctx!(
+        keystore_internal.verify_password(password),
+        "err keystore_internal.verify_password()",
+    );
+
+    null()
+}
+
+/// Rewrites the keystore in place under the new password with a fresh salt and iv
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for reads and writes of their lens,
+/// both passwords must be readable for their lens. `kdf_options` may be null (the current parameters
+/// are kept) or point to valid options
+#[no_mangle]
+pub unsafe extern "C" fn reencrypt_keystore(
+    keystore: *mut Keystore,
+    old_password: SizedStrConst,
+    new_password: SizedStrConst,
+    kdf_options: *const KdfOptions,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(old_password.validate("old_password"));
+    unwrap_or_handle!(new_password.validate("new_password"));
+
+    let old_password = old_password.as_slice();
+    let new_password = new_password.as_slice();
+    let kdf = unwrap_or_handle_ctx!(KdfOptions::read_kdf_internal(kdf_options), "err KdfOptions::read_kdf_internal()");
+
+    let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+    let keystore_internal = unwrap_or_handle_ctx!(
+        keystore_internal.reencrypt(old_password, new_password, kdf),
+        "err keystore_internal.reencrypt()",
+    );
+    unwrap_or_handle_ctx!(keystore.write(keystore_internal), "err keystore.write()");
+
+    null()
+}
+
+/// Renders the Web3 Secret Storage v3 document, a null `json.str` only writes the required len.
+/// Otherwise the written len is written to `json_len`, both include the NUL terminator
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are readable for their lens and `address` must
+/// be readable for its len. `json.str` may be null, otherwise it must be writable for `json.len`.
+/// `json_len` must be valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn keystore_to_json(
+    keystore: *const Keystore,
+    address: SizedStrConst,
+    network: Network,
+    mut json: SizedStr,
+    json_len: *mut usize,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(address.validate("address"));
+    unwrap_or_handle!(keystore_ffi::ptr_is_null(json_len, "json_len"));
+
+    let keystore_internal = unwrap_or_handle_ctx!(keystore.read(), "err keystore.read()");
+    let address = address.as_slice();
+
+    let len = unwrap_or_handle_ctx!(
+        keystore_json::v3_len(&keystore_internal, address, &network),
+        "err keystore_json::v3_len()",
+    ) + 1;
+
+    // Length-query mode: the caller only asks for the buffer size
+    if json.is_null() {
+        *json_len = len;
+        return null();
+    }
+
+    unwrap_or_handle!(json.validate_capacity(len, "json"));
+
+    let output = json.as_mut_slice();
+    let capacity = output.len();
+
+    let mut remaining = &mut output[..];
+    unwrap_or_handle_ctx!(
+        keystore_json::write_v3(&mut remaining, &keystore_internal, address, &network),
+        "err keystore_json::write_v3()",
+    );
+
+    // A larger buffer is NUL-terminated right after the document
+    let written = capacity - remaining.len();
+    output[written] = 0;
+    *json_len = written + 1;
+
+    null()
+}
+
+/// Parses a Web3 Secret Storage v3 document into the keystore
+///
+/// # Safety
+///
+/// `keystore` must point to a keystore whose fields are valid for writes of their lens, 32-byte
+/// salts of other wallets need an `IMPORTED_SALT_LEN` buffer. `json` must be readable for its len
+#[no_mangle]
+pub unsafe extern "C" fn keystore_from_json(
+    keystore: *mut Keystore,
+    json: SizedStrConst,
+) -> ErrorPtr {
+    let keystore = keystore.read();
+
+    unwrap_or_handle!(keystore.validate());
+    unwrap_or_handle!(json.validate("json"));
+
+    let keystore_internal = unwrap_or_handle_ctx!(
+        keystore_json::parse_v3(json.as_slice()),
+        "err keystore_json::parse_v3()",
+    );
+    unwrap_or_handle_ctx!(keystore.write(keystore_internal), "err keystore.write()");
+
+    null()
+}
+
+/// Generates a BIP39 English phrase of 12, 15, 18, 21 or 24 words
+///
+/// # Safety
+///
+/// `mnemonic` must be writable for its len
+#[no_mangle]
+pub unsafe extern "C" fn generate_mnemonic(
+    word_count: usize,
+    mnemonic: SizedStr,
+) -> ErrorPtr {
+    unwrap_or_handle!(mnemonic.validate(MNEMONIC_LEN, "mnemonic"));
+
+    let (phrase, phrase_len) = unwrap_or_handle_ctx!(mnemonic::generate(word_count), "err mnemonic::generate()");
+    mnemonic.write_bytes(&phrase[..phrase_len]);
+
+    null()
+}
+
+/// Checks the words and the checksum of a BIP39 phrase. An invalid phrase is not an error,
+/// it sets `is_valid` to false
+///
+/// # Safety
+///
+/// `mnemonic` must be readable for its len and `is_valid` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn validate_mnemonic(
+    mnemonic: SizedStrConst,
+    is_valid: *mut bool,
+) -> ErrorPtr {
+    unwrap_or_handle!(mnemonic.validate("mnemonic"));
+    unwrap_or_handle!(keystore_ffi::ptr_is_null(is_valid, "is_valid"));
+
+    *is_valid = mnemonic::to_entropy(mnemonic.as_slice()).is_ok();
+
+    null()
+}
+
+/// Derives the 64-byte BIP39 seed of a phrase, pass an empty passphrase if there is none. The
+/// phrase isn't validated, call `validate_mnemonic` first
+///
+/// # Safety
+///
+/// `mnemonic` and `passphrase` must be readable for their lens, `seed` must be writable for its
+/// len
+#[no_mangle]
+pub unsafe extern "C" fn mnemonic_to_seed(
+    mnemonic: SizedStrConst,
+    passphrase: SizedStrConst,
+    seed: SizedBytes,
+) -> ErrorPtr {
+    unwrap_or_handle!(mnemonic.validate("mnemonic"));
+    unwrap_or_handle!(passphrase.validate("passphrase"));
+    unwrap_or_handle!(seed.validate(SEED_LEN, "seed"));
+
+    let seed_bytes = unwrap_or_handle_ctx!(
+        mnemonic::to_seed(mnemonic.as_slice(), passphrase.as_slice()),
+        "err mnemonic::to_seed()",
+    );
+    seed.write_bytes(seed_bytes.as_slice());
+
+    null()
+}
+
+/// Frees an error string returned by the functions of this library
+///
+/// # Safety
+///
+/// `ptr` must be null or a pointer returned by this library that was not freed yet, it must not be
+/// used after the call
 #[no_mangle]
 pub unsafe extern "C" fn free_cstring(ptr: *mut c_char) {
     if ptr.is_null() {
@@ -28,8 +28,8 @@ macro_rules! unwrap_or_handle_ctx {
 
 #[macro_export]
 macro_rules! bail {
-    ($msg:expr, $($arg:expr)+ $(,)?) => {
-        return CString::new(format!($msg, $($arg)*))
+    ($msg:expr, $($arg:expr),+ $(,)?) => {
+        return CString::new(format!($msg, $($arg),+))
             .expect("CString::new() failed")
             .into_raw()
     };
@@ -0,0 +1,253 @@
+mod english;
+
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256, Sha512};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+use crate::error::{KsgenError, Result};
+
+// 24 words of up to 8 characters separated by spaces
+pub const MAX_PHRASE_LEN: usize = 24 * 8 + 23;
+pub const MAX_ENTROPY_LEN: usize = 32;
+pub const SEED_LEN: usize = 64;
+
+// "mnemonic" followed by the NFKD-normalized passphrase
+const SALT_PREFIX: &str = "mnemonic";
+const PBKDF2_ROUNDS: u32 = 2048;
+
+const WORD_BITS: usize = 11;
+
+/// Generates a phrase of 12, 15, 18, 21 or 24 words, returns the phrase buffer and its len
+pub fn generate(word_count: usize) -> Result<(Zeroizing<[u8; MAX_PHRASE_LEN]>, usize)> {
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err(KsgenError::invalid_mnemonic(format!("unsupported word count ({})", word_count)));
+    }
+
+    let mut entropy = Zeroizing::new([0; MAX_ENTROPY_LEN]);
+    let entropy_len = word_count * 4 / 3;
+    OsRng.fill_bytes(&mut entropy[..entropy_len]);
+
+    from_entropy(&entropy[..entropy_len])
+}
+
+/// Encodes 16, 20, 24, 28 or 32 bytes of entropy with the SHA-256 checksum into a phrase
+pub fn from_entropy(entropy: &[u8]) -> Result<(Zeroizing<[u8; MAX_PHRASE_LEN]>, usize)> {
+    if !matches!(entropy.len(), 16 | 20 | 24 | 28 | 32) {
+        return Err(KsgenError::invalid_mnemonic(format!("unsupported entropy len ({})", entropy.len())));
+    }
+
+    // The entropy followed by the checksum byte, only its first len / 4 bits are used
+    let mut bits = Zeroizing::new([0; MAX_ENTROPY_LEN + 1]);
+    bits[..entropy.len()].copy_from_slice(entropy);
+    bits[entropy.len()] = Sha256::digest(entropy)[0];
+
+    let word_count = (entropy.len() * 8 + entropy.len() / 4) / WORD_BITS;
+
+    let mut phrase = Zeroizing::new([0; MAX_PHRASE_LEN]);
+    let mut len = 0;
+
+    for i in 0..word_count {
+        if i > 0 {
+            phrase[len] = b' ';
+            len += 1;
+        }
+
+        let word = english::WORDS[read_index(bits.as_slice(), i)].as_bytes();
+        phrase[len..len + word.len()].copy_from_slice(word);
+        len += word.len();
+    }
+
+    Ok((phrase, len))
+}
+
+/// Validates the words and the checksum of a phrase, returns the entropy buffer and its len
+pub fn to_entropy(phrase: &[u8]) -> Result<(Zeroizing<[u8; MAX_ENTROPY_LEN]>, usize)> {
+    let mut bits = Zeroizing::new([0; MAX_ENTROPY_LEN + 1]);
+    let mut word_count = 0;
+
+    for word in phrase.split(u8::is_ascii_whitespace).filter(|x| !x.is_empty()) {
+        if word_count == 24 {
+            return Err(KsgenError::invalid_mnemonic("more than 24 words".into()));
+        }
+
+        let index = english::WORDS.binary_search_by(|x| x.as_bytes().cmp(word))
+            .map_err(|_| KsgenError::invalid_mnemonic(format!("unknown word at position {}", word_count + 1)))?;
+        write_index(bits.as_mut_slice(), word_count, index);
+
+        word_count += 1;
+    }
+
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err(KsgenError::invalid_mnemonic(format!("unsupported word count ({})", word_count)));
+    }
+
+    let entropy_len = word_count * 4 / 3;
+    let checksum_bits = entropy_len / 4;
+
+    let mut entropy = Zeroizing::new([0; MAX_ENTROPY_LEN]);
+    entropy[..entropy_len].copy_from_slice(&bits[..entropy_len]);
+
+    let mask = 0xff << (8 - checksum_bits);
+    if (Sha256::digest(&entropy[..entropy_len])[0] ^ bits[entropy_len]) & mask != 0 {
+        return Err(KsgenError::InvalidChecksumErr);
+    }
+
+    Ok((entropy, entropy_len))
+}
+
+/// Derives the 64-byte BIP39 seed of a UTF-8 phrase, the passphrase may be empty. As BIP39
+/// specifies, neither the words nor the checksum are checked; call `to_entropy` first for that
+pub fn to_seed(phrase: &[u8], passphrase: &[u8]) -> Result<Zeroizing<[u8; SEED_LEN]>> {
+    let phrase = std::str::from_utf8(phrase)
+        .map_err(|_| KsgenError::invalid_mnemonic("the phrase is not UTF-8".into()))?;
+    let passphrase = std::str::from_utf8(passphrase)
+        .map_err(|_| KsgenError::invalid_mnemonic("the passphrase is not UTF-8".into()))?;
+
+    // Words are hashed separated by single spaces whatever the input whitespace is
+    let phrase = nfkd_joined("", phrase.split_whitespace());
+    let salt = nfkd_joined(SALT_PREFIX, std::iter::once(passphrase));
+
+    let mut seed = Zeroizing::new([0; SEED_LEN]);
+    pbkdf2::pbkdf2_hmac::<Sha512>(phrase.as_bytes(), salt.as_bytes(), PBKDF2_ROUNDS, seed.as_mut_slice());
+
+    Ok(seed)
+}
+
+// NFKD-normalizes the parts separated by single spaces after the prefix. The buffer is sized up
+// front, so no reallocation leaves a copy of the secret behind
+fn nfkd_joined<'a>(prefix: &str, parts: impl Iterator<Item = &'a str> + Clone) -> Zeroizing<String> {
+    let len = parts.clone()
+        .enumerate()
+        .map(|(i, x)| (i > 0) as usize + x.nfkd().map(char::len_utf8).sum::<usize>())
+        .sum::<usize>();
+
+    let mut output = Zeroizing::new(String::with_capacity(prefix.len() + len));
+    output.push_str(prefix);
+    for (i, part) in parts.enumerate() {
+        if i > 0 {
+            output.push(' ');
+        }
+        output.extend(part.nfkd());
+    }
+
+    output
+}
+
+// Reads the 11-bit word index at the position from a big-endian bit buffer
+fn read_index(bits: &[u8], position: usize) -> usize {
+    let start = position * WORD_BITS;
+
+    (start..start + WORD_BITS).fold(0, |acc, i| {
+        (acc << 1) | ((bits[i / 8] >> (7 - i % 8)) & 1) as usize
+    })
+}
+
+fn write_index(bits: &mut [u8], position: usize, index: usize) {
+    let start = position * WORD_BITS;
+
+    for (n, i) in (start..start + WORD_BITS).enumerate() {
+        if (index >> (WORD_BITS - 1 - n)) & 1 == 1 {
+            bits[i / 8] |= 1 << (7 - i % 8);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP39 test vectors, all seeds use the "TREZOR" passphrase
+    const VECTORS: [(&str, &str, &str); 6] = [
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+            "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+            "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+        ),
+        (
+            "9e885d952ad362caeb4efe34a8e91bd2",
+            "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+            "274ddc525802f7c828d8ef7ddbcdc5304e87ac3535913611fbbfa986d0c9e5476c91689f9c8a54fd55bd38606aa6a8595ad213d4c9c9f9aca3fb217069a41028",
+        ),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (entropy, phrase, seed) in VECTORS {
+            let entropy = hex::decode(entropy).unwrap();
+
+            let (encoded, len) = from_entropy(&entropy).unwrap();
+            assert_eq!(&encoded[..len], phrase.as_bytes());
+
+            let (decoded, len) = to_entropy(phrase.as_bytes()).unwrap();
+            assert_eq!(&decoded[..len], entropy.as_slice());
+
+            assert_eq!(hex::encode(to_seed(phrase.as_bytes(), b"TREZOR").unwrap()), seed);
+        }
+    }
+
+    #[test]
+    fn test_generate() {
+        for word_count in [12, 15, 18, 21, 24] {
+            let (phrase, len) = generate(word_count).unwrap();
+            assert_eq!(phrase[..len].split(|x| x == &b' ').count(), word_count);
+            assert!(to_entropy(&phrase[..len]).is_ok());
+        }
+
+        assert!(generate(13).is_err());
+    }
+
+    #[test]
+    fn test_invalid_phrase() {
+        // Checksum mismatch
+        assert!(matches!(
+            to_entropy(b"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon"),
+            Err(KsgenError::InvalidChecksumErr),
+        ));
+        assert!(to_entropy(b"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abou").is_err());
+        assert!(to_entropy(b"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").is_err());
+
+        // Extra whitespace doesn't change the seed
+        let seed = to_seed(b"  legal winner thank year wave sausage worth useful legal winner\tthank yellow\n", b"TREZOR").unwrap();
+        assert_eq!(hex::encode(seed), VECTORS[1].2);
+    }
+
+    #[test]
+    fn test_seed_without_validation() {
+        // BIP39 derives seeds of phrases with a wrong checksum too
+        let phrase = b"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert_eq!(
+            hex::encode(to_seed(phrase, b"TREZOR").unwrap()),
+            "ee6db4e1561f47f4c008aea51ebf7597a94aad4429cbe6c3fb85326f38c6027281ba8d7bc4591042e69709f6884119b4c0950faee27eb5e1e912c2a309d4b088",
+        );
+
+        // Passphrases have no len limit
+        let phrase = VECTORS[0].1.as_bytes();
+        assert_eq!(
+            hex::encode(to_seed(phrase, &[b'a'; 300]).unwrap()),
+            "599383629d8ba654289983a781fc0131f05680f5b1b73cad06eefa31e9824466d84f2acdf54ab5e1c78176abd6a5042cf334443bfee8ae619c84ba7346f7efd5",
+        );
+
+        assert!(to_seed(b"\xff", b"").is_err());
+    }
+}
\ No newline at end of file